@@ -1,9 +1,67 @@
 #![no_std]
-use soroban_sdk::{contract, contractimpl, contracttype, token, Address, String, Env, Vec};
+use soroban_sdk::{contract, contractclient, contractimpl, contracttype, token, xdr::ToXdr, Address, Bytes, BytesN, String, Env, Symbol, Vec};
 
 #[contract]
 pub struct EscrowContract;
 
+// Domain tag prefixed to every signed approval message
+const APPROVAL_DOMAIN: &[u8] = b"relay:escrow:approve:v1";
+
+// Inactivity (in seconds) after which an employee can be replaced without consent
+const ABANDONMENT_TIMEOUT: u64 = 30 * 24 * 60 * 60;
+
+// Oldest oracle price (in seconds) accepted for a pegged escrow
+const MAX_PRICE_AGE: u64 = 15 * 60;
+
+// Asset identifier used by SEP-40 price oracles
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+// Price reported by a SEP-40 oracle, in its base currency
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+// Subset of the SEP-40 price oracle interface used by pegged escrows
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
+
+// Entry point of the ReviewContract called when an escrow completes
+#[contractclient(name = "ReviewClient")]
+pub trait ReviewInterface {
+    fn leave_review(
+        env: Env,
+        job_id: String,
+        reviewer: Address,
+        reviewee: Address,
+        rating: u32,
+        comment: String,
+    ) -> u64;
+}
+
+// Entry point of the TippingContract called when an escrow completes
+#[contractclient(name = "TippingClient")]
+pub trait TippingInterface {
+    fn send_tip(
+        env: Env,
+        job_id: String,
+        from: Address,
+        to: Address,
+        token: Address,
+        amount: i128,
+        message: String,
+    ) -> u64;
+}
+
 // Escrow record
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -11,12 +69,192 @@ pub struct Escrow {
     pub id: String,                    // Unique escrow ID
     pub job_id: String,                 // References job in database
     pub employer: Address,             // Who is paying (employer)
-    pub employee: Address,             // Who will receive payment
+    pub funder: Address,               // Whose funds are locked; refunds go here
+    pub employee: Option<Address>,     // Who will receive payment (None until assigned)
     pub amount: i128,                  // Amount in stroops
+    pub released: i128,                // Part of `amount` already paid out
     pub token: Address,                // Token contract (USDC or XLM)
     pub status: EscrowStatus,          // Current status
     pub created_at: u64,               // Creation timestamp
     pub deadline: Option<u64>,         // Optional deadline
+    pub kind: EscrowKind,              // How funds are released
+    pub amendments: Vec<Amendment>,    // History of amount/deadline changes
+    pub approvers: Vec<Address>,       // Delegated approvers (empty = employer approves)
+    pub threshold: u32,                // Approvals needed from `approvers`
+    pub approvals: Vec<Address>,       // Approvers who have signed off so far
+    pub approved_at: Option<u64>,      // When the escrow completed
+    pub closed_at: Option<u64>,        // When the escrow left the locked state for any reason
+    pub claimable: bool,               // Open escrow any worker may claim first come, first served
+}
+
+// Hours logged against an hourly escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TimesheetEntry {
+    pub id: u32,
+    pub hours: u32,
+    pub period_start: u64,             // Start of the period worked
+    pub period_end: u64,               // End of the period worked
+    pub description_hash: BytesN<32>,  // Hash of the off-chain work description
+    pub amount: i128,                  // hours * rate
+    pub status: TimesheetStatus,
+    pub submitted_at: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TimesheetStatus {
+    Pending,     // Waiting for the employer
+    Approved,    // Paid out of the locked balance
+    Rejected,    // Declined by the employer
+}
+
+// Linear vesting schedule for a streaming escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StreamSchedule {
+    pub start: u64,                    // Vesting starts at this timestamp
+    pub cliff: Option<u64>,            // Nothing vests before this timestamp
+    pub end: u64,                      // Fully vested at this timestamp
+}
+
+// Terms of a recurring retainer escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RetainerTerms {
+    pub period_amount: i128,           // Locked for each period
+    pub period_length: u64,            // Seconds per period
+    pub period: u32,                   // Index of the current period
+    pub period_start: u64,             // Start of the current period
+    pub renew: bool,                   // Lock the next period when this one ends
+}
+
+// Agreed value of a pegged escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Peg {
+    pub oracle: Address,               // SEP-40 oracle pricing the escrow token
+    pub value: i128,                   // Owed in the oracle's base currency, at its decimals
+}
+
+// Performance bond the employee deposits against an escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Bond {
+    pub token: Address,                // Token the bond is held in
+    pub amount: i128,                  // Required bond amount
+    pub arbiter: Address,              // Who can forfeit the bond in a dispute
+    pub posted: bool,                  // Employee has deposited the bond
+    pub forfeited: i128,               // Amount paid to the employer so far
+    pub returned: bool,                // Remaining bond sent back to the employee
+}
+
+// One entry in an escrow's activity log
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct HistoryEntry {
+    pub timestamp: u64,
+    pub actor: Address,                // Who triggered it (the contract for automatic steps)
+    pub action: EscrowAction,
+    pub amount: i128,                  // See `EscrowAction`
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EscrowAction {
+    Created,             // Funds locked; amount = amount locked
+    Assigned,            // Employee assigned or claimed the escrow
+    Reassigned,          // Handed over to a new employee
+    Amended,             // Top-up, extension or mutual change; amount = new escrow amount
+    Renewed,             // Retainer re-locked for another period; amount = amount locked
+    RenewalStopped,      // Retainer set to complete at the end of the current period
+    TimesheetRejected,   // Employer rejected a timesheet entry; amount = entry amount
+    ApproversSet,        // Delegated approvers changed; amount = new threshold
+    BondPosted,          // Employee deposited the bond; amount = bond amount
+    BondForfeited,       // Arbiter paid part of the bond to the employer; amount = amount forfeited
+    Approval,            // One delegated approver signed off
+    Released,            // Partial payout; amount = amount paid
+    SettlementProposed,  // amount = proposed employee amount
+    Approved,            // Completed; amount = total paid out
+    Cancelled,           // amount = total paid out before the refund
+    Settled,             // amount = total paid out, including the agreed split
+}
+
+// Proposed split of a locked escrow's remaining funds on cancellation
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub proposer: Address,             // Employer or employee; the other side accepts
+    pub employee_amount: i128,         // Paid to the employee
+    pub refund: i128,                  // Returned to the funder
+    pub proposed_at: u64,
+}
+
+// A handover of an escrow from one employee to another
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reassignment {
+    pub from: Address,                 // Previous employee
+    pub to: Address,                   // New employee
+    pub abandoned: bool,               // Forced after the abandonment timeout rather than consented
+    pub timestamp: u64,
+}
+
+// A change to an escrow's amount or deadline
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Amendment {
+    pub kind: AmendmentKind,
+    pub prev_amount: i128,
+    pub new_amount: i128,
+    pub prev_deadline: Option<u64>,
+    pub new_deadline: Option<u64>,
+    pub timestamp: u64,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AmendmentKind {
+    TopUp,       // Employer added funds
+    Extension,   // Employer pushed the deadline back
+    Mutual,      // Employer and employee agreed to new terms
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum EscrowKind {
+    Standard,    // Single employee paid on approval
+    Bounty,      // Open submissions, employer picks winners
+    Team,        // Several payees split the amount by share
+    Hourly,      // Approved timesheet entries paid at an hourly rate up to `amount`
+    Stream,      // Amount vests linearly over a schedule
+    Retainer,    // Fixed amount re-locked from the employer every period
+    Pegged,      // Pays a fixed value in the oracle's base currency out of collateral
+}
+
+// Work submitted to a bounty
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BountyEntry {
+    pub submitter: Address,
+    pub entry_hash: BytesN<32>,        // Hash of the off-chain submission
+    pub submitted_at: u64,
+}
+
+// A team member's cut of a team escrow
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Share {
+    pub payee: Address,
+    pub bps: u32,                      // Basis points of the amount (10000 = 100%)
+}
+
+// Amount paid to a single address
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payout {
+    pub to: Address,
+    pub amount: i128,
 }
 
 #[contracttype]
@@ -25,6 +263,42 @@ pub enum EscrowStatus {
     Locked,      // Funds are locked in escrow
     Approved,    // Employer approved, payment released
     Cancelled,   // Employer cancelled, funds returned
+    Settled,     // Cancelled by agreement, funds split between both sides
+}
+
+// Parameters for one escrow in a `create_escrows` batch
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EscrowRequest {
+    pub escrow_id: String,
+    pub job_id: String,
+    pub employee: Option<Address>,
+    pub token: Address,
+    pub amount: i128,
+    pub deadline: Option<u64>,
+    pub claimable: bool,
+}
+
+// Running escrow totals for one user in one token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq, Default)]
+pub struct EscrowStats {
+    pub locked: i128,                  // Currently held in escrow
+    pub released: i128,                // Paid out (to employees as employer, earned as employee)
+    pub refunded: i128,                // Returned to the funder
+    pub locked_count: u32,             // Escrows still locked
+    pub approved_count: u32,           // Escrows completed
+    pub cancelled_count: u32,          // Escrows cancelled
+    pub settled_count: u32,            // Escrows cancelled with an agreed split
+}
+
+// Employer review and tip recorded together with an approval
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Feedback {
+    pub rating: u32,                   // 1-5 stars for the employee
+    pub comment: String,               // Review text
+    pub tip_amount: i128,              // Tip in the escrow token (0 = no tip)
 }
 
 // Storage keys
@@ -33,6 +307,30 @@ pub enum DataKey {
     Escrow(String),                    // Individual escrow by ID
     EmployerEscrows(Address),          // List of escrow IDs by employer
     EmployeeEscrows(Address),          // List of escrow IDs by employee
+    EscrowCounter,                     // Counter for contract-assigned escrow IDs
+    JobEscrow(String),                 // Latest escrow ID for a job
+    BountyEntries(String),             // Submissions to a bounty escrow
+    BountyWinners(String),             // Payouts chosen for a bounty escrow
+    TeamShares(String),                // Payee shares for a team escrow
+    Bond(String),                      // Employee bond for an escrow
+    HourlyRate(String),                // Rate per hour for an hourly escrow
+    Timesheet(String),                 // Timesheet entries for an hourly escrow
+    Stream(String),                    // Vesting schedule for a streaming escrow
+    Retainer(String),                  // Terms for a retainer escrow
+    Peg(String),                       // Agreed value for a pegged escrow
+    LastActivity(String),              // Last time the employee acted on an escrow
+    Reassignments(String),             // Employee handovers for an escrow
+    Settlement(String),                // Pending cancellation split for an escrow
+    History(String),                   // Activity log for an escrow
+    SigningKey(String, BytesN<32>),    // Approver an ed25519 key signs for, per escrow
+    ApprovalNonce(String),             // Next nonce expected for a signed approval
+    Admin,                             // Contract admin
+    ReviewContract,                    // ReviewContract notified on approval
+    TippingContract,                   // TippingContract notified on approval
+    EmployerStats(Address, Address),   // Totals per (employer, token)
+    EmployeeStats(Address, Address),   // Totals per (employee, token)
+    EmployerStatusEscrows(Address, EscrowStatus), // Employer's escrow IDs with a status
+    EmployeeStatusEscrows(Address, EscrowStatus), // Employee's escrow IDs with a status
 }
 
 #[contractimpl]
@@ -40,12 +338,14 @@ impl EscrowContract {
     
     /// Create a new escrow and lock funds
     /// The employer's funds are transferred into this contract's custody
+    /// Pass no employee to fund an open job before anyone is assigned
+    #[allow(clippy::too_many_arguments)]
     pub fn create_escrow(
         env: Env,
         escrow_id: String,
         job_id: String,
         employer: Address,
-        employee: Address,
+        employee: Option<Address>,
         token: Address,
         amount: i128,
         deadline: Option<u64>,
@@ -53,104 +353,282 @@ impl EscrowContract {
         // Verify caller is the employer
         employer.require_auth();
         
-        // Validate amount is positive
-        assert!(amount > 0, "Amount must be positive");
+        open_escrow(
+            &env,
+            escrow_id,
+            job_id,
+            employer,
+            employee,
+            token,
+            amount,
+            deadline,
+            EscrowKind::Standard,
+            None,
+        )
+    }
+    
+    /// Create an open escrow that any worker can claim (first come, first served)
+    /// instead of waiting for the employer to assign someone
+    pub fn create_claimable_escrow(
+        env: Env,
+        escrow_id: String,
+        job_id: String,
+        employer: Address,
+        token: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
         
-        // Don't allow self-escrow
-        assert!(employer != employee, "Cannot escrow to yourself");
+        let mut escrow = open_escrow(
+            &env,
+            escrow_id,
+            job_id,
+            employer,
+            None,
+            token,
+            amount,
+            deadline,
+            EscrowKind::Standard,
+            None,
+        );
+        allow_claims(&env, &mut escrow);
+        escrow
+    }
+    
+    /// Create several escrows under one employer signature
+    /// If any request is invalid the whole batch fails
+    pub fn create_escrows(env: Env, employer: Address, requests: Vec<EscrowRequest>) -> Vec<Escrow> {
+        // Verify caller is the employer
+        employer.require_auth();
         
-        // Check if escrow already exists
-        let existing_escrow: Option<Escrow> = env
-            .storage()
-            .instance()
-            .get(&DataKey::Escrow(escrow_id.clone()));
+        assert!(!requests.is_empty(), "No escrows");
         
-        assert!(existing_escrow.is_none(), "Escrow already exists");
+        let mut escrows = Vec::new(&env);
+        for request in requests.iter() {
+            let mut escrow = open_escrow(
+                &env,
+                request.escrow_id,
+                request.job_id,
+                employer.clone(),
+                request.employee,
+                request.token,
+                request.amount,
+                request.deadline,
+                EscrowKind::Standard,
+                None,
+            );
+            if request.claimable {
+                allow_claims(&env, &mut escrow);
+            }
+            escrows.push_back(escrow);
+        }
+        escrows
+    }
+    
+    /// Create a new escrow with a contract-assigned ID ("escrow-<n>")
+    /// IDs are generated from a monotonically increasing counter
+    pub fn create_escrow_auto(
+        env: Env,
+        job_id: String,
+        employer: Address,
+        employee: Option<Address>,
+        token: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
         
-        // Transfer tokens from employer to this contract (lock them)
-        let token_client = token::Client::new(&env, &token);
-        token_client.transfer(&employer, &env.current_contract_address(), &amount);
+        // Skip any counter value already taken by a caller-supplied ID
+        let mut counter: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EscrowCounter)
+            .unwrap_or(0);
+        let mut escrow_id = generated_id(&env, counter);
+        while env.storage().instance().has(&DataKey::Escrow(escrow_id.clone())) {
+            counter += 1;
+            escrow_id = generated_id(&env, counter);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::EscrowCounter, &(counter + 1));
         
-        // Create escrow record
-        let escrow = Escrow {
-            id: escrow_id.clone(),
+        open_escrow(
+            &env,
+            escrow_id,
             job_id,
-            employer: employer.clone(),
-            employee: employee.clone(),
-            amount,
+            employer,
+            employee,
             token,
-            status: EscrowStatus::Locked,
-            created_at: env.ledger().timestamp(),
+            amount,
             deadline,
-        };
+            EscrowKind::Standard,
+            None,
+        )
+    }
+    
+    /// Approve and release funds to employee
+    /// The employer approves directly, unless delegated approvers are set,
+    /// in which case funds release once `threshold` distinct approvers sign off
+    /// The employer can attach feedback to leave a review and tip in the same call
+    pub fn approve_escrow(
+        env: Env,
+        escrow_id: String,
+        approver: Address,
+        feedback: Option<Feedback>,
+    ) {
+        // Verify caller signed transaction
+        approver.require_auth();
         
-        // Store escrow
-        env.storage()
-            .instance()
-            .set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+        let released = approve_as(&env, escrow_id.clone(), approver.clone());
+        
+        if let Some(feedback) = feedback {
+            let escrow: Escrow = env
+                .storage()
+                .instance()
+                .get(&DataKey::Escrow(escrow_id))
+                .unwrap();
+            assert!(escrow.employer == approver, "Only the employer can leave feedback");
+            assert!(released, "Escrow not released");
+            send_feedback(&env, &escrow, feedback);
+        }
+    }
+    
+    /// Approve several escrows under one signature
+    /// If any approval is invalid the whole batch fails; returns each escrow after approval
+    pub fn approve_escrows(env: Env, approver: Address, escrow_ids: Vec<String>) -> Vec<Escrow> {
+        // Verify caller signed transaction
+        approver.require_auth();
         
-        // Add to employer's list
-        let mut employer_escrows: Vec<String> = env
+        assert!(!escrow_ids.is_empty(), "No escrows");
+        
+        let mut escrows = Vec::new(&env);
+        for escrow_id in escrow_ids.iter() {
+            approve_as(&env, escrow_id.clone(), approver.clone());
+            escrows.push_back(
+                env.storage()
+                    .instance()
+                    .get(&DataKey::Escrow(escrow_id))
+                    .unwrap(),
+            );
+        }
+        escrows
+    }
+    
+    /// Register an ed25519 key that can approve on behalf of the employer or a delegated approver
+    /// Lets a relayer submit approvals signed off-chain
+    pub fn register_signing_key(
+        env: Env,
+        escrow_id: String,
+        approver: Address,
+        public_key: BytesN<32>,
+    ) {
+        // Verify caller is the approver the key signs for
+        approver.require_auth();
+        
+        let escrow: Escrow = env
             .storage()
             .instance()
-            .get(&DataKey::EmployerEscrows(employer.clone()))
-            .unwrap_or(Vec::new(&env));
-        employer_escrows.push_back(escrow_id.clone());
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(
+            escrow.employer == approver || escrow.approvers.contains(&approver),
+            "Unauthorized"
+        );
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
         env.storage()
             .instance()
-            .set(&DataKey::EmployerEscrows(employer), &employer_escrows);
-        
-        // Add to employee's list
-        let mut employee_escrows: Vec<String> = env
+            .set(&DataKey::SigningKey(escrow_id, public_key), &approver);
+    }
+    
+    /// Approve using an off-chain ed25519 signature over `get_approval_message(escrow_id, nonce)`
+    /// Anyone can submit it (and pay the fees); the nonce must match `get_approval_nonce`
+    pub fn approve_with_signature(
+        env: Env,
+        escrow_id: String,
+        public_key: BytesN<32>,
+        signature: BytesN<64>,
+        nonce: u64,
+    ) {
+        let approver: Address = env
             .storage()
             .instance()
-            .get(&DataKey::EmployeeEscrows(employee.clone()))
-            .unwrap_or(Vec::new(&env));
-        employee_escrows.push_back(escrow_id.clone());
+            .get(&DataKey::SigningKey(escrow_id.clone(), public_key.clone()))
+            .expect("Signing key not registered");
+        
+        // Each nonce can only be used once
+        let nonce_key = DataKey::ApprovalNonce(escrow_id.clone());
+        let expected: u64 = env.storage().instance().get(&nonce_key).unwrap_or(0);
+        assert!(nonce == expected, "Invalid nonce");
+        env.storage().instance().set(&nonce_key, &(nonce + 1));
+        
+        // Panics if the signature doesn't match
+        let message = approval_message(&env, &escrow_id, nonce);
+        env.crypto().ed25519_verify(&public_key, &message, &signature);
+        
+        approve_as(&env, escrow_id, approver);
+    }
+    
+    /// Get the bytes a signing key must sign to approve with `nonce`
+    pub fn get_approval_message(env: Env, escrow_id: String, nonce: u64) -> Bytes {
+        approval_message(&env, &escrow_id, nonce)
+    }
+    
+    /// Get the nonce the next signed approval must use
+    pub fn get_approval_nonce(env: Env, escrow_id: String) -> u64 {
         env.storage()
             .instance()
-            .set(&DataKey::EmployeeEscrows(employee), &employee_escrows);
-        
-        escrow
+            .get(&DataKey::ApprovalNonce(escrow_id))
+            .unwrap_or(0)
     }
     
-    /// Approve and release funds to employee
-    /// Employer must call this to release locked funds
-    pub fn approve_escrow(
+    /// Delegate approval to a set of approvers with an M-of-N threshold
+    /// Passing an empty list hands approval back to the employer
+    pub fn set_approvers(
         env: Env,
         escrow_id: String,
         employer: Address,
-    ) {
+        approvers: Vec<Address>,
+        threshold: u32,
+    ) -> Escrow {
         // Verify caller is the employer
         employer.require_auth();
         
-        // Get escrow
         let mut escrow: Escrow = env
             .storage()
             .instance()
             .get(&DataKey::Escrow(escrow_id.clone()))
             .expect("Escrow not found");
         
-        // Verify it's the correct employer
         assert!(escrow.employer == employer, "Unauthorized");
-        
-        // Check escrow is locked
         assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(escrow.approvals.is_empty(), "Approvals already started");
         
-        // Check deadline if applicable
-        if let Some(deadline) = escrow.deadline {
-            assert!(env.ledger().timestamp() < deadline, "Escrow expired");
+        if approvers.is_empty() {
+            assert!(threshold == 0, "Invalid threshold");
+        } else {
+            assert!(threshold >= 1 && threshold <= approvers.len(), "Invalid threshold");
+        }
+        for (i, approver) in approvers.iter().enumerate() {
+            assert!(
+                approvers.iter().skip(i + 1).all(|other| other != approver),
+                "Duplicate approver"
+            );
         }
         
-        // Transfer locked tokens from contract to employee
-        let token_client = token::Client::new(&env, &escrow.token);
-        token_client.transfer(&env.current_contract_address(), &escrow.employee, &escrow.amount);
-        
-        // Update escrow status
-        escrow.status = EscrowStatus::Approved;
+        escrow.approvers = approvers;
+        escrow.threshold = threshold;
+        log_history(&env, &escrow_id, employer, EscrowAction::ApproversSet, threshold as i128);
         env.storage()
             .instance()
             .set(&DataKey::Escrow(escrow_id), &escrow);
+        
+        escrow
     }
     
     /// Cancel escrow and return funds to employer
@@ -176,53 +654,2146 @@ impl EscrowContract {
         // Only allow cancelling if still locked
         assert!(escrow.status == EscrowStatus::Locked, "Cannot cancel, escrow already processed");
         
+        // Hourly escrows settle through close_hourly_escrow
+        assert!(escrow.kind != EscrowKind::Hourly, "Use close_hourly_escrow");
+        
+        // Streams pay out what has vested and refund the rest
+        if escrow.kind == EscrowKind::Stream {
+            let owed = vested_amount(&env, &escrow) - escrow.released;
+            if owed > 0 {
+                release_funds(&env, &escrow, owed);
+                escrow.released += owed;
+                log_history(&env, &escrow_id, env.current_contract_address(), EscrowAction::Released, owed);
+            }
+        }
+        
+        // A retainer period that ran out unapproved is owed to the employee
+        if escrow.kind == EscrowKind::Retainer {
+            let terms: RetainerTerms = env
+                .storage()
+                .instance()
+                .get(&DataKey::Retainer(escrow_id.clone()))
+                .expect("Not a retainer escrow");
+            let owed = escrow.amount - escrow.released;
+            if owed > 0 && env.ledger().timestamp() >= terms.period_start + terms.period_length {
+                release_funds(&env, &escrow, owed);
+                escrow.released += owed;
+                log_history(&env, &escrow_id, env.current_contract_address(), EscrowAction::Released, owed);
+            }
+        }
+        
+        // Bounties with submissions can only be refunded after the deadline
+        if escrow.kind == EscrowKind::Bounty {
+            let entries: Vec<BountyEntry> = env
+                .storage()
+                .instance()
+                .get(&DataKey::BountyEntries(escrow_id.clone()))
+                .unwrap_or(Vec::new(&env));
+            assert!(entries.is_empty(), "Bounty has entries");
+        }
+        
         // Transfer tokens back to employer
-        let token_client = token::Client::new(&env, &escrow.token);
-        token_client.transfer(&env.current_contract_address(), &escrow.employer, &escrow.amount);
+        let refund = escrow.amount - escrow.released;
+        if refund > 0 {
+            let token_client = token::Client::new(&env, &escrow.token);
+            token_client.transfer(&env.current_contract_address(), &escrow.funder, &refund);
+            stats_refund(&env, &escrow, refund);
+        }
+        
+        // Whatever the arbiter didn't forfeit goes back to the employee
+        return_bond(&env, &escrow);
         
         // Update escrow status
         escrow.status = EscrowStatus::Cancelled;
+        record_status(&env, &mut escrow, employer);
         env.storage()
             .instance()
             .set(&DataKey::Escrow(escrow_id), &escrow);
     }
     
-    /// Get escrow details
-    pub fn get_escrow(env: Env, escrow_id: String) -> Escrow {
-        env.storage()
-            .instance()
-            .get(&DataKey::Escrow(escrow_id))
-            .expect("Escrow not found")
-    }
-    
-    /// Get all escrows for an employer
-    pub fn get_employer_escrows(env: Env, employer: Address) -> Vec<String> {
-        env.storage()
+    /// Propose cancelling an escrow with `employee_amount` paid for work done
+    /// and the rest refunded; replaces any earlier proposal
+    pub fn propose_settlement(
+        env: Env,
+        escrow_id: String,
+        proposer: Address,
+        employee_amount: i128,
+    ) -> Settlement {
+        // Verify caller is the proposer
+        proposer.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
             .instance()
-            .get(&DataKey::EmployerEscrows(employer))
-            .unwrap_or(Vec::new(&env))
-    }
-    
-    /// Get all escrows for an employee
-    pub fn get_employee_escrows(env: Env, employee: Address) -> Vec<String> {
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(
+            !matches!(escrow.kind, EscrowKind::Bounty | EscrowKind::Team),
+            "Escrow has no single employee"
+        );
+        let employee = escrow.employee.clone().expect("No employee assigned");
+        assert!(proposer == escrow.employer || proposer == employee, "Unauthorized");
+        
+        let remaining = escrow.amount - escrow.released;
+        assert!(
+            employee_amount >= 0 && employee_amount <= remaining,
+            "Invalid split"
+        );
+        
+        log_history(&env, &escrow_id, proposer.clone(), EscrowAction::SettlementProposed, employee_amount);
+        
+        let settlement = Settlement {
+            proposer,
+            employee_amount,
+            refund: remaining - employee_amount,
+            proposed_at: env.ledger().timestamp(),
+        };
         env.storage()
             .instance()
-            .get(&DataKey::EmployeeEscrows(employee))
-            .unwrap_or(Vec::new(&env))
+            .set(&DataKey::Settlement(escrow_id), &settlement);
+        
+        settlement
     }
     
-    /// Check if escrow is locked
-    pub fn is_locked(env: Env, escrow_id: String) -> bool {
-        let escrow: Escrow = env
+    /// Accept the other side's settlement proposal, paying out both sides
+    pub fn accept_settlement(env: Env, escrow_id: String, accepter: Address) -> Escrow {
+        // Verify caller is the accepting party
+        accepter.require_auth();
+        
+        let mut escrow: Escrow = env
             .storage()
             .instance()
-            .get(&DataKey::Escrow(escrow_id))
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        let settlement: Settlement = env
+            .storage()
+            .instance()
+            .get(&DataKey::Settlement(escrow_id.clone()))
+            .expect("No settlement proposed");
+        
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        let employee = escrow.employee.clone().expect("No employee assigned");
+        assert!(accepter == escrow.employer || accepter == employee, "Unauthorized");
+        assert!(accepter != settlement.proposer, "Cannot accept your own proposal");
+        
+        // Top-ups or payouts since the proposal change what's left to split
+        assert!(
+            settlement.employee_amount + settlement.refund == escrow.amount - escrow.released,
+            "Settlement out of date"
+        );
+        
+        if settlement.employee_amount > 0 {
+            release_funds(&env, &escrow, settlement.employee_amount);
+            escrow.released += settlement.employee_amount;
+        }
+        if settlement.refund > 0 {
+            let token_client = token::Client::new(&env, &escrow.token);
+            token_client.transfer(&env.current_contract_address(), &escrow.funder, &settlement.refund);
+            stats_refund(&env, &escrow, settlement.refund);
+        }
+        
+        return_bond(&env, &escrow);
+        
+        escrow.status = EscrowStatus::Settled;
+        record_status(&env, &mut escrow, accepter);
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+        
+        escrow
+    }
+    
+    /// Get the pending settlement proposal for an escrow, if any
+    pub fn get_settlement(env: Env, escrow_id: String) -> Option<Settlement> {
+        env.storage().instance().get(&DataKey::Settlement(escrow_id))
+    }
+    
+    /// Create a bounty escrow that anyone can submit entries to
+    /// If no winner is picked by the deadline the employer can take a refund
+    pub fn create_bounty(
+        env: Env,
+        escrow_id: String,
+        job_id: String,
+        employer: Address,
+        token: Address,
+        amount: i128,
+        deadline: u64,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        assert!(deadline > env.ledger().timestamp(), "Deadline must be in the future");
+        
+        open_escrow(
+            &env,
+            escrow_id,
+            job_id,
+            employer,
+            None,
+            token,
+            amount,
+            Some(deadline),
+            EscrowKind::Bounty,
+            None,
+        )
+    }
+    
+    /// Create an escrow paid out to a team
+    /// Shares must add up to 10000 basis points
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_team_escrow(
+        env: Env,
+        escrow_id: String,
+        job_id: String,
+        employer: Address,
+        shares: Vec<Share>,
+        token: Address,
+        amount: i128,
+        deadline: Option<u64>,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        assert!(!shares.is_empty(), "No payees");
+        let mut total_bps: u32 = 0;
+        for (i, share) in shares.iter().enumerate() {
+            assert!(share.bps > 0, "Share must be positive");
+            assert!(share.payee != employer, "Cannot escrow to yourself");
+            assert!(
+                shares.iter().skip(i + 1).all(|other| other.payee != share.payee),
+                "Duplicate payee"
+            );
+            total_bps += share.bps;
+        }
+        assert!(total_bps == 10_000, "Shares must total 10000 bps");
+        
+        let escrow = open_escrow(
+            &env,
+            escrow_id.clone(),
+            job_id,
+            employer,
+            None,
+            token,
+            amount,
+            deadline,
+            EscrowKind::Team,
+            None,
+        );
+        
+        env.storage()
+            .instance()
+            .set(&DataKey::TeamShares(escrow_id.clone()), &shares);
+        
+        // Every payee sees the escrow in their list and stats
+        for share in shares.iter() {
+            stats_join(&env, &escrow, share.payee.clone());
+            index_employee_escrow(&env, share.payee, escrow_id.clone());
+        }
+        
+        escrow
+    }
+    
+    /// Get payee shares for a team escrow
+    pub fn get_team_shares(env: Env, escrow_id: String) -> Vec<Share> {
+        env.storage()
+            .instance()
+            .get(&DataKey::TeamShares(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Log hours against an hourly escrow for the employer to review
+    pub fn submit_timesheet(
+        env: Env,
+        escrow_id: String,
+        employee: Address,
+        hours: u32,
+        period_start: u64,
+        period_end: u64,
+        description_hash: BytesN<32>,
+    ) -> u32 {
+        // Verify caller is the employee
+        employee.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.kind == EscrowKind::Hourly, "Not an hourly escrow");
+        assert!(escrow.employee == Some(employee), "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(hours > 0, "Hours must be positive");
+        assert!(period_end > period_start, "Invalid period");
+        
+        let rate: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::HourlyRate(escrow_id.clone()))
+            .expect("Rate not found");
+        
+        let mut entries: Vec<TimesheetEntry> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Timesheet(escrow_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        
+        let entry_id = entries.len();
+        entries.push_back(TimesheetEntry {
+            id: entry_id,
+            hours,
+            period_start,
+            period_end,
+            description_hash,
+            amount: rate * hours as i128,
+            status: TimesheetStatus::Pending,
+            submitted_at: env.ledger().timestamp(),
+        });
+        env.storage()
+            .instance()
+            .set(&DataKey::Timesheet(escrow_id.clone()), &entries);
+        record_activity(&env, escrow_id);
+        
+        entry_id
+    }
+    
+    /// Approve or reject a pending timesheet entry
+    /// Approved entries are paid immediately from the locked balance
+    pub fn review_timesheet(
+        env: Env,
+        escrow_id: String,
+        employer: Address,
+        entry_id: u32,
+        approve: bool,
+    ) -> TimesheetEntry {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.kind == EscrowKind::Hourly, "Not an hourly escrow");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let mut entries: Vec<TimesheetEntry> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Timesheet(escrow_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        let mut entry = entries.get(entry_id).expect("Entry not found");
+        assert!(entry.status == TimesheetStatus::Pending, "Entry already reviewed");
+        
+        if approve {
+            assert!(entry.amount <= escrow.amount - escrow.released, "Entry exceeds remaining balance");
+            
+            release_funds(&env, &escrow, entry.amount);
+            escrow.released += entry.amount;
+            log_history(&env, &escrow_id, employer.clone(), EscrowAction::Released, entry.amount);
+            env.storage()
+                .instance()
+                .set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+            
+            entry.status = TimesheetStatus::Approved;
+        } else {
+            log_history(&env, &escrow_id, employer.clone(), EscrowAction::TimesheetRejected, entry.amount);
+            entry.status = TimesheetStatus::Rejected;
+        }
+        
+        entries.set(entry_id, entry.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::Timesheet(escrow_id), &entries);
+        
+        entry
+    }
+    
+    /// Close an hourly escrow and refund the unused balance to the employer
+    /// All entries must be reviewed first
+    pub fn close_hourly_escrow(env: Env, escrow_id: String, employer: Address) -> i128 {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.kind == EscrowKind::Hourly, "Not an hourly escrow");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let entries: Vec<TimesheetEntry> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Timesheet(escrow_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        assert!(
+            entries.iter().all(|e| e.status != TimesheetStatus::Pending),
+            "Timesheet entries pending review"
+        );
+        
+        let unused = escrow.amount - escrow.released;
+        if unused > 0 {
+            let token_client = token::Client::new(&env, &escrow.token);
+            token_client.transfer(&env.current_contract_address(), &escrow.funder, &unused);
+            stats_refund(&env, &escrow, unused);
+        }
+        
+        return_bond(&env, &escrow);
+        
+        escrow.status = EscrowStatus::Approved;
+        record_status(&env, &mut escrow, employer);
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+        
+        unused
+    }
+    
+    /// Get all timesheet entries for an hourly escrow
+    pub fn get_timesheet(env: Env, escrow_id: String) -> Vec<TimesheetEntry> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Timesheet(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Withdraw everything vested so far
+    pub fn withdraw_vested(env: Env, escrow_id: String, employee: Address) -> i128 {
+        // Verify caller is the employee
+        employee.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.kind == EscrowKind::Stream, "Not a streaming escrow");
+        assert!(escrow.employee == Some(employee.clone()), "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let available = vested_amount(&env, &escrow) - escrow.released;
+        assert!(available > 0, "Nothing vested");
+        
+        release_funds(&env, &escrow, available);
+        escrow.released += available;
+        log_history(&env, &escrow_id, employee.clone(), EscrowAction::Released, available);
+        record_activity(&env, escrow_id.clone());
+        
+        // Fully paid streams are complete
+        if escrow.released == escrow.amount {
+            return_bond(&env, &escrow);
+            escrow.status = EscrowStatus::Approved;
+            record_status(&env, &mut escrow, employee);
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+        
+        available
+    }
+    
+    /// Get how much of a streaming escrow has vested (including withdrawn funds)
+    pub fn get_vested_amount(env: Env, escrow_id: String) -> i128 {
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found");
+        
+        vested_amount(&env, &escrow)
+    }
+    
+    /// Get the vesting schedule for a streaming escrow
+    pub fn get_stream_schedule(env: Env, escrow_id: String) -> StreamSchedule {
+        env.storage()
+            .instance()
+            .get(&DataKey::Stream(escrow_id))
+            .expect("Not a streaming escrow")
+    }
+    
+    /// Create a retainer escrow that locks the first period now
+    /// Later periods are pulled with `transfer_from`, so the employer must
+    /// approve this contract as a spender for future periods
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_retainer_escrow(
+        env: Env,
+        escrow_id: String,
+        job_id: String,
+        employer: Address,
+        employee: Address,
+        token: Address,
+        period_amount: i128,
+        period_length: u64,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        assert!(period_length > 0, "Period length must be positive");
+        
+        let escrow = open_escrow(
+            &env,
+            escrow_id.clone(),
+            job_id,
+            employer,
+            Some(employee),
+            token,
+            period_amount,
+            None,
+            EscrowKind::Retainer,
+            None,
+        );
+        
+        let terms = RetainerTerms {
+            period_amount,
+            period_length,
+            period: 0,
+            period_start: env.ledger().timestamp(),
+            renew: true,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Retainer(escrow_id), &terms);
+        
+        escrow
+    }
+    
+    /// Approve the current retainer period and pay the employee
+    pub fn approve_period(env: Env, escrow_id: String, employer: Address) -> i128 {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.kind == EscrowKind::Retainer, "Not a retainer escrow");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let owed = escrow.amount - escrow.released;
+        assert!(owed > 0, "Period already paid");
+        
+        release_funds(&env, &escrow, owed);
+        escrow.released += owed;
+        log_history(&env, &escrow_id, employer.clone(), EscrowAction::Released, owed);
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+        
+        owed
+    }
+    
+    /// Move a retainer to its next period once the current one has ended
+    /// Anyone can call this. An unapproved period is paid to the employee on timeout,
+    /// then the next period is locked from the employer's allowance. If renewal was
+    /// stopped or the allowance/balance can't cover it, the retainer completes instead.
+    pub fn roll_period(env: Env, escrow_id: String) -> RetainerTerms {
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.kind == EscrowKind::Retainer, "Not a retainer escrow");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let mut terms: RetainerTerms = env
+            .storage()
+            .instance()
+            .get(&DataKey::Retainer(escrow_id.clone()))
+            .expect("Not a retainer escrow");
+        let period_end = terms.period_start + terms.period_length;
+        assert!(env.ledger().timestamp() >= period_end, "Period not over");
+        
+        // Pay out the period if the employer never approved it
+        let owed = escrow.amount - escrow.released;
+        let contract = env.current_contract_address();
+        if owed > 0 {
+            release_funds(&env, &escrow, owed);
+            escrow.released += owed;
+            log_history(&env, &escrow_id, contract.clone(), EscrowAction::Released, owed);
+        }
+        
+        let token_client = token::Client::new(&env, &escrow.token);
+        let can_renew = terms.renew
+            && token_client.allowance(&escrow.funder, &contract) >= terms.period_amount
+            && token_client.balance(&escrow.funder) >= terms.period_amount;
+        
+        if can_renew {
+            token_client.transfer_from(&contract, &escrow.funder, &contract, &terms.period_amount);
+            stats_lock(&env, &escrow, terms.period_amount);
+            escrow.amount += terms.period_amount;
+            log_history(&env, &escrow_id, contract, EscrowAction::Renewed, terms.period_amount);
+            terms.period += 1;
+            terms.period_start = period_end;
+        } else {
+            terms.renew = false;
+            return_bond(&env, &escrow);
+            escrow.status = EscrowStatus::Approved;
+            record_status(&env, &mut escrow, contract);
+        }
+        
+        env.storage()
+            .instance()
+            .set(&DataKey::Retainer(escrow_id.clone()), &terms);
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+        
+        terms
+    }
+    
+    /// Stop a retainer from renewing; the current period still runs to the end
+    pub fn stop_renewal(env: Env, escrow_id: String, employer: Address) -> RetainerTerms {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let mut terms: RetainerTerms = env
+            .storage()
+            .instance()
+            .get(&DataKey::Retainer(escrow_id.clone()))
+            .expect("Not a retainer escrow");
+        terms.renew = false;
+        log_history(&env, &escrow_id, employer, EscrowAction::RenewalStopped, 0);
+        env.storage()
+            .instance()
+            .set(&DataKey::Retainer(escrow_id), &terms);
+        
+        terms
+    }
+    
+    /// Get the terms of a retainer escrow
+    pub fn get_retainer_terms(env: Env, escrow_id: String) -> RetainerTerms {
+        env.storage()
+            .instance()
+            .get(&DataKey::Retainer(escrow_id))
+            .expect("Not a retainer escrow")
+    }
+    
+    /// Get the agreed value of a pegged escrow
+    pub fn get_peg(env: Env, escrow_id: String) -> Peg {
+        env.storage()
+            .instance()
+            .get(&DataKey::Peg(escrow_id))
+            .expect("Not a pegged escrow")
+    }
+    
+    /// Get how many tokens a pegged escrow would pay the employee at the current price
+    pub fn get_pegged_payout(env: Env, escrow_id: String) -> i128 {
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found");
+        pegged_payout(&env, &escrow)
+    }
+    
+    /// Submit an entry to a bounty before its deadline
+    /// Resubmitting replaces the submitter's previous entry
+    pub fn submit_entry(
+        env: Env,
+        escrow_id: String,
+        submitter: Address,
+        entry_hash: BytesN<32>,
+    ) {
+        // Verify caller is the submitter
+        submitter.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.kind == EscrowKind::Bounty, "Not a bounty");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(escrow.employer != submitter, "Cannot submit to your own bounty");
+        if let Some(deadline) = escrow.deadline {
+            assert!(env.ledger().timestamp() < deadline, "Escrow expired");
+        }
+        
+        let entry = BountyEntry {
+            submitter: submitter.clone(),
+            entry_hash,
+            submitted_at: env.ledger().timestamp(),
+        };
+        
+        let mut entries: Vec<BountyEntry> = env
+            .storage()
+            .instance()
+            .get(&DataKey::BountyEntries(escrow_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        match entries.iter().position(|e| e.submitter == submitter) {
+            Some(index) => entries.set(index as u32, entry),
+            None => entries.push_back(entry),
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::BountyEntries(escrow_id), &entries);
+    }
+    
+    /// Pay one or more bounty winners
+    /// Any amount not awarded goes back to the employer
+    pub fn pick_winners(
+        env: Env,
+        escrow_id: String,
+        employer: Address,
+        winners: Vec<Payout>,
+    ) {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.kind == EscrowKind::Bounty, "Not a bounty");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(!winners.is_empty(), "No winners");
+        
+        let entries: Vec<BountyEntry> = env
+            .storage()
+            .instance()
+            .get(&DataKey::BountyEntries(escrow_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        
+        // Every winner must have submitted once, and awards can't exceed the bounty
+        let mut total: i128 = 0;
+        for (i, winner) in winners.iter().enumerate() {
+            assert!(winner.amount > 0, "Amount must be positive");
+            assert!(
+                entries.iter().any(|e| e.submitter == winner.to),
+                "Winner has no entry"
+            );
+            assert!(
+                winners.iter().skip(i + 1).all(|other| other.to != winner.to),
+                "Duplicate winner"
+            );
+            total += winner.amount;
+        }
+        assert!(total <= escrow.amount, "Awards exceed bounty");
+        
+        // Winners join the escrow's stats before it pays out and closes
+        env.storage()
+            .instance()
+            .set(&DataKey::BountyWinners(escrow_id.clone()), &winners);
+        for winner in winners.iter() {
+            stats_join(&env, &escrow, winner.to);
+        }
+        
+        let token_client = token::Client::new(&env, &escrow.token);
+        stats_unlock(&env, &escrow, total);
+        for winner in winners.iter() {
+            token_client.transfer(&env.current_contract_address(), &winner.to, &winner.amount);
+            stats_release(&env, &escrow, &winner.to, winner.amount);
+            index_employee_escrow(&env, winner.to, escrow_id.clone());
+        }
+        if total < escrow.amount {
+            token_client.transfer(&env.current_contract_address(), &escrow.funder, &(escrow.amount - total));
+            stats_refund(&env, &escrow, escrow.amount - total);
+        }
+        
+        escrow.released = total;
+        escrow.status = EscrowStatus::Approved;
+        record_status(&env, &mut escrow, employer);
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+    }
+    
+    /// Refund a bounty to the employer when no winner was picked by the deadline
+    pub fn refund_bounty(env: Env, escrow_id: String, employer: Address) {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.kind == EscrowKind::Bounty, "Not a bounty");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        if let Some(deadline) = escrow.deadline {
+            assert!(env.ledger().timestamp() >= deadline, "Bounty still open");
+        }
+        
+        let token_client = token::Client::new(&env, &escrow.token);
+        token_client.transfer(&env.current_contract_address(), &escrow.funder, &escrow.amount);
+        stats_refund(&env, &escrow, escrow.amount);
+        
+        escrow.status = EscrowStatus::Cancelled;
+        record_status(&env, &mut escrow, employer);
+        env.storage()
+            .instance()
+            .set(&DataKey::Escrow(escrow_id), &escrow);
+    }
+    
+    /// Get all entries submitted to a bounty
+    pub fn get_bounty_entries(env: Env, escrow_id: String) -> Vec<BountyEntry> {
+        env.storage()
+            .instance()
+            .get(&DataKey::BountyEntries(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the winners paid by a bounty
+    pub fn get_bounty_winners(env: Env, escrow_id: String) -> Vec<Payout> {
+        env.storage()
+            .instance()
+            .get(&DataKey::BountyWinners(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Assign an employee to an open escrow
+    /// Only the employer can assign
+    pub fn assign_employee(
+        env: Env,
+        escrow_id: String,
+        employer: Address,
+        employee: Address,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        
+        set_employee(&env, &mut escrow, employee);
+        log_history(&env, &escrow.id, employer, EscrowAction::Assigned, 0);
+        escrow
+    }
+    
+    /// Claim an open escrow as its employee (first come, first served)
+    pub fn claim_escrow(env: Env, escrow_id: String, employee: Address) -> Escrow {
+        // Verify caller is the worker claiming the job
+        employee.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found");
+        
+        assert!(escrow.claimable, "Escrow not open to claims");
+        
+        set_employee(&env, &mut escrow, employee.clone());
+        log_history(&env, &escrow.id, employee, EscrowAction::Assigned, 0);
+        escrow
+    }
+    
+    /// Show the employee is still working, resetting the abandonment timeout
+    pub fn check_in(env: Env, escrow_id: String, employee: Address) {
+        // Verify caller is the employee
+        employee.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employee == Some(employee), "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        record_activity(&env, escrow_id);
+    }
+    
+    /// Hand a locked escrow over to a new employee
+    /// The current employee must consent, unless they've been inactive
+    /// for the abandonment timeout
+    pub fn reassign_employee(
+        env: Env,
+        escrow_id: String,
+        employer: Address,
+        new_employee: Address,
+    ) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        // Hourly, stream and retainer escrows carry work owed to the current employee
+        assert!(
+            matches!(escrow.kind, EscrowKind::Standard | EscrowKind::Pegged),
+            "Only standard and pegged escrows can be reassigned"
+        );
+        let current = escrow.employee.clone().expect("No employee assigned");
+        assert!(current != new_employee, "Already assigned to this employee");
+        
+        let now = env.ledger().timestamp();
+        let last_activity = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastActivity(escrow_id.clone()))
+            .unwrap_or(escrow.created_at);
+        let abandoned = now >= last_activity + ABANDONMENT_TIMEOUT;
+        if !abandoned {
+            current.require_auth();
+        }
+        
+        // A posted bond has to be settled with the arbiter first,
+        // then the new employee owes a fresh one
+        let bond_key = DataKey::Bond(escrow_id.clone());
+        if let Some(mut bond) = env.storage().instance().get::<_, Bond>(&bond_key) {
+            assert!(
+                !bond.posted || bond.returned || bond.forfeited == bond.amount,
+                "Employee bond posted"
+            );
+            bond.posted = false;
+            bond.forfeited = 0;
+            bond.returned = false;
+            env.storage().instance().set(&bond_key, &bond);
+        }
+        
+        // Take the escrow off the current employee's lists and totals
+        remove_id(&env, DataKey::EmployeeEscrows(current.clone()), &escrow_id);
+        remove_id(&env, DataKey::EmployeeStatusEscrows(current.clone(), EscrowStatus::Locked), &escrow_id);
+        let key = DataKey::EmployeeStats(current.clone(), escrow.token.clone());
+        let mut stats = load_stats(&env, &key);
+        stats.locked -= escrow.amount - escrow.released;
+        stats.locked_count -= 1;
+        env.storage().instance().set(&key, &stats);
+        
+        // A split proposed with the old employee no longer applies
+        env.storage()
+            .instance()
+            .remove(&DataKey::Settlement(escrow_id.clone()));
+        
+        // Sign-offs were for the old employee's work
+        escrow.approvals = Vec::new(&env);
+        escrow.employee = None;
+        set_employee(&env, &mut escrow, new_employee.clone());
+        
+        let mut reassignments: Vec<Reassignment> = env
+            .storage()
+            .instance()
+            .get(&DataKey::Reassignments(escrow_id.clone()))
+            .unwrap_or(Vec::new(&env));
+        reassignments.push_back(Reassignment {
+            from: current,
+            to: new_employee,
+            abandoned,
+            timestamp: now,
+        });
+        env.storage()
+            .instance()
+            .set(&DataKey::Reassignments(escrow_id.clone()), &reassignments);
+        log_history(&env, &escrow_id, employer, EscrowAction::Reassigned, 0);
+        
+        escrow
+    }
+    
+    /// Get the employee handovers of an escrow
+    pub fn get_reassignments(env: Env, escrow_id: String) -> Vec<Reassignment> {
+        env.storage()
+            .instance()
+            .get(&DataKey::Reassignments(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the last time the employee acted on an escrow
+    pub fn get_last_activity(env: Env, escrow_id: String) -> u64 {
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        env.storage()
+            .instance()
+            .get(&DataKey::LastActivity(escrow_id))
+            .unwrap_or(escrow.created_at)
+    }
+    
+    /// Add funds to a locked escrow
+    pub fn top_up_escrow(env: Env, escrow_id: String, employer: Address, amount: i128) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(amount > 0, "Amount must be positive");
+        
+        // Sponsored escrows are topped up from the funder's allowance
+        let token_client = token::Client::new(&env, &escrow.token);
+        let contract = env.current_contract_address();
+        if escrow.funder == employer {
+            token_client.transfer(&employer, &contract, &amount);
+        } else {
+            escrow.funder.require_auth();
+            token_client.transfer_from(&contract, &escrow.funder, &contract, &amount);
+        }
+        stats_lock(&env, &escrow, amount);
+        
+        let new_amount = escrow.amount + amount;
+        let deadline = escrow.deadline;
+        record_amendment(&env, &mut escrow, AmendmentKind::TopUp, new_amount, deadline);
+        escrow
+    }
+    
+    /// Push an escrow's deadline later
+    pub fn extend_deadline(env: Env, escrow_id: String, employer: Address, new_deadline: u64) -> Escrow {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let deadline = escrow.deadline.expect("Escrow has no deadline");
+        assert!(new_deadline > deadline, "Deadline can only be extended");
+        
+        let amount = escrow.amount;
+        record_amendment(&env, &mut escrow, AmendmentKind::Extension, amount, Some(new_deadline));
+        escrow
+    }
+    
+    /// Reduce the amount or change the deadline with both parties' consent
+    /// Any reduction is refunded to the employer
+    pub fn amend_escrow(
+        env: Env,
+        escrow_id: String,
+        employer: Address,
+        employee: Address,
+        new_amount: i128,
+        new_deadline: Option<u64>,
+    ) -> Escrow {
+        // Both sides must sign the amendment
+        employer.require_auth();
+        employee.require_auth();
+        
+        let mut escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.employee == Some(employee), "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(new_amount > 0, "Amount must be positive");
+        assert!(new_amount <= escrow.amount, "Use top_up_escrow to increase the amount");
+        assert!(new_amount >= escrow.released, "Amount below what was already paid");
+        if let Some(deadline) = new_deadline {
+            assert!(deadline > env.ledger().timestamp(), "Deadline must be in the future");
+        }
+        
+        // Return the difference to the employer
+        let refund = escrow.amount - new_amount;
+        if refund > 0 {
+            let token_client = token::Client::new(&env, &escrow.token);
+            token_client.transfer(&env.current_contract_address(), &escrow.funder, &refund);
+            stats_refund(&env, &escrow, refund);
+        }
+        
+        record_activity(&env, escrow_id);
+        record_amendment(&env, &mut escrow, AmendmentKind::Mutual, new_amount, new_deadline);
+        escrow
+    }
+    
+    /// Require the employee to post a bond before work starts
+    /// The arbiter can forfeit some or all of it to the employer in a dispute
+    pub fn require_bond(
+        env: Env,
+        escrow_id: String,
+        employer: Address,
+        token: Address,
+        amount: i128,
+        arbiter: Address,
+    ) -> Bond {
+        // Verify caller is the employer
+        employer.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employer == employer, "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(
+            !matches!(escrow.kind, EscrowKind::Bounty | EscrowKind::Team),
+            "Escrow has no single employee"
+        );
+        assert!(amount > 0, "Amount must be positive");
+        assert!(arbiter != employer, "Arbiter must be independent");
+        assert!(escrow.employee != Some(arbiter.clone()), "Arbiter must be independent");
+        assert!(
+            !env.storage().instance().has(&DataKey::Bond(escrow_id.clone())),
+            "Bond already required"
+        );
+        
+        let bond = Bond {
+            token,
+            amount,
+            arbiter,
+            posted: false,
+            forfeited: 0,
+            returned: false,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Bond(escrow_id), &bond);
+        
+        bond
+    }
+    
+    /// Deposit the required bond as the escrow's employee
+    pub fn post_bond(env: Env, escrow_id: String, employee: Address) -> Bond {
+        // Verify caller is the employee
+        employee.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        assert!(escrow.employee == Some(employee.clone()), "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        
+        let mut bond: Bond = env
+            .storage()
+            .instance()
+            .get(&DataKey::Bond(escrow_id.clone()))
+            .expect("No bond required");
+        assert!(!bond.posted, "Bond already posted");
+        
+        let token_client = token::Client::new(&env, &bond.token);
+        token_client.transfer(&employee, env.current_contract_address(), &bond.amount);
+        record_activity(&env, escrow_id.clone());
+        log_history(&env, &escrow_id, employee, EscrowAction::BondPosted, bond.amount);
+        
+        bond.posted = true;
+        env.storage()
+            .instance()
+            .set(&DataKey::Bond(escrow_id), &bond);
+        
+        bond
+    }
+    
+    /// Forfeit part or all of a posted bond to the employer
+    /// Only the bond's arbiter can rule, and only while the escrow is open
+    pub fn forfeit_bond(env: Env, escrow_id: String, arbiter: Address, amount: i128) -> Bond {
+        // Verify caller is the arbiter
+        arbiter.require_auth();
+        
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id.clone()))
+            .expect("Escrow not found");
+        
+        let mut bond: Bond = env
+            .storage()
+            .instance()
+            .get(&DataKey::Bond(escrow_id.clone()))
+            .expect("No bond required");
+        
+        assert!(bond.arbiter == arbiter, "Unauthorized");
+        assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+        assert!(bond.posted, "Bond not posted");
+        assert!(amount > 0, "Amount must be positive");
+        assert!(amount <= bond.amount - bond.forfeited, "Amount exceeds bond");
+        
+        let token_client = token::Client::new(&env, &bond.token);
+        token_client.transfer(&env.current_contract_address(), &escrow.employer, &amount);
+        
+        bond.forfeited += amount;
+        log_history(&env, &escrow_id, arbiter, EscrowAction::BondForfeited, amount);
+        env.storage()
+            .instance()
+            .set(&DataKey::Bond(escrow_id), &bond);
+        
+        bond
+    }
+    
+    /// Get the bond attached to an escrow, if any
+    pub fn get_bond(env: Env, escrow_id: String) -> Option<Bond> {
+        env.storage().instance().get(&DataKey::Bond(escrow_id))
+    }
+    
+    /// Set the admin that configures integrations at deployment
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+    
+    /// Configure the review and tipping contracts used for approval feedback
+    pub fn set_integrations(env: Env, admin: Address, review_contract: Address, tipping_contract: Address) {
+        admin.require_auth();
+        
+        let stored: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+        assert!(stored == admin, "Unauthorized");
+        
+        env.storage().instance().set(&DataKey::ReviewContract, &review_contract);
+        env.storage().instance().set(&DataKey::TippingContract, &tipping_contract);
+    }
+    
+    /// Get the configured review contract, if any
+    pub fn get_review_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::ReviewContract)
+    }
+    
+    /// Get the configured tipping contract, if any
+    pub fn get_tipping_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TippingContract)
+    }
+    
+    /// Get an employer's escrow totals in a token
+    pub fn get_employer_stats(env: Env, employer: Address, token: Address) -> EscrowStats {
+        load_stats(&env, &DataKey::EmployerStats(employer, token))
+    }
+    
+    /// Get an employee's escrow totals in a token
+    pub fn get_employee_stats(env: Env, employee: Address, token: Address) -> EscrowStats {
+        load_stats(&env, &DataKey::EmployeeStats(employee, token))
+    }
+    
+    /// Get the activity log of an escrow, oldest first
+    pub fn get_escrow_history(env: Env, escrow_id: String) -> Vec<HistoryEntry> {
+        env.storage()
+            .instance()
+            .get(&DataKey::History(escrow_id))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get escrow details
+    pub fn get_escrow(env: Env, escrow_id: String) -> Escrow {
+        env.storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id))
+            .expect("Escrow not found")
+    }
+    
+    /// Get the latest escrow created for a job
+    pub fn get_escrow_by_job(env: Env, job_id: String) -> Escrow {
+        let escrow_id: String = env
+            .storage()
+            .instance()
+            .get(&DataKey::JobEscrow(job_id))
+            .expect("Escrow not found");
+        
+        Self::get_escrow(env, escrow_id)
+    }
+    
+    /// Get all escrows for an employer
+    pub fn get_employer_escrows(env: Env, employer: Address) -> Vec<String> {
+        env.storage()
+            .instance()
+            .get(&DataKey::EmployerEscrows(employer))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// List an employer's escrows with a status, optionally only in one token
+    /// Skips the first `start` matches and returns at most `limit`
+    pub fn get_employer_escrows_by_status(
+        env: Env,
+        employer: Address,
+        status: EscrowStatus,
+        token: Option<Address>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Escrow> {
+        list_escrows(&env, DataKey::EmployerStatusEscrows(employer, status), token, start, limit)
+    }
+    
+    /// List an employee's escrows with a status, optionally only in one token
+    /// Skips the first `start` matches and returns at most `limit`
+    pub fn get_employee_escrows_by_status(
+        env: Env,
+        employee: Address,
+        status: EscrowStatus,
+        token: Option<Address>,
+        start: u32,
+        limit: u32,
+    ) -> Vec<Escrow> {
+        list_escrows(&env, DataKey::EmployeeStatusEscrows(employee, status), token, start, limit)
+    }
+    
+    /// Get all escrows for an employee
+    pub fn get_employee_escrows(env: Env, employee: Address) -> Vec<String> {
+        env.storage()
+            .instance()
+            .get(&DataKey::EmployeeEscrows(employee))
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Check if escrow is locked
+    pub fn is_locked(env: Env, escrow_id: String) -> bool {
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(escrow_id))
             .expect("Escrow not found");
         
         escrow.status == EscrowStatus::Locked
     }
 }
 
+// Record an approval from `approver` and release funds once enough approvals are in
+// Returns whether the funds were released
+fn approve_as(env: &Env, escrow_id: String, approver: Address) -> bool {
+    // Get escrow
+    let mut escrow: Escrow = env
+        .storage()
+        .instance()
+        .get(&DataKey::Escrow(escrow_id.clone()))
+        .expect("Escrow not found");
+    
+    // Verify it's the employer or one of the delegated approvers
+    if escrow.approvers.is_empty() {
+        assert!(escrow.employer == approver, "Unauthorized");
+    } else {
+        assert!(escrow.approvers.contains(&approver), "Unauthorized");
+        assert!(!escrow.approvals.contains(&approver), "Already approved");
+    }
+    
+    // Check escrow is locked
+    assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+    
+    // Bounties and hourly escrows have their own payout flows
+    assert!(
+        matches!(escrow.kind, EscrowKind::Standard | EscrowKind::Team | EscrowKind::Pegged),
+        "Not a standard escrow"
+    );
+    
+    // Check deadline if applicable
+    if let Some(deadline) = escrow.deadline {
+        assert!(env.ledger().timestamp() < deadline, "Escrow expired");
+    }
+    
+    // Collect approvals until the threshold is met
+    if !escrow.approvers.is_empty() {
+        escrow.approvals.push_back(approver.clone());
+        if escrow.approvals.len() < escrow.threshold {
+            log_history(env, &escrow_id, approver, EscrowAction::Approval, 0);
+            env.storage()
+                .instance()
+                .set(&DataKey::Escrow(escrow_id), &escrow);
+            return false;
+        }
+    }
+    
+    // Pegged escrows pay the agreed value at today's price
+    let payout = if escrow.kind == EscrowKind::Pegged {
+        pegged_payout(env, &escrow)
+    } else {
+        escrow.amount
+    };
+    
+    // Transfer locked tokens from contract to employee (or team)
+    release_funds(env, &escrow, payout);
+    
+    // Refund collateral left over after a pegged payout
+    let excess = escrow.amount - payout;
+    if excess > 0 {
+        let token_client = token::Client::new(env, &escrow.token);
+        token_client.transfer(&env.current_contract_address(), &escrow.funder, &excess);
+        stats_refund(env, &escrow, excess);
+    }
+    
+    // Give the employee their bond back
+    return_bond(env, &escrow);
+    
+    // Update escrow status
+    escrow.released = payout;
+    escrow.status = EscrowStatus::Approved;
+    record_status(env, &mut escrow, approver);
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrow(escrow_id), &escrow);
+    
+    true
+}
+
+// Entry points with more than seven arguments besides `env`
+// `#[contractimpl]` generates an argument helper for each of them that clippy also
+// flags, and an `allow` on the function doesn't reach it, so they get their own block
+#[allow(clippy::too_many_arguments)]
+mod long_entry_points {
+    use super::*;
+    
+    #[contractimpl]
+    impl EscrowContract {
+        /// Create an escrow funded from a prior token allowance
+        /// `funder` (the employer, a company treasury, the platform...) must authorize it and have approved
+        /// this contract as a spender; the employer keeps approval rights and refunds go to the funder
+        pub fn create_escrow_from_allowance(
+            env: Env,
+            escrow_id: String,
+            job_id: String,
+            employer: Address,
+            funder: Address,
+            employee: Option<Address>,
+            token: Address,
+            amount: i128,
+            deadline: Option<u64>,
+        ) -> Escrow {
+            // Verify caller is the employer and the funder agrees to this escrow
+            employer.require_auth();
+            if funder != employer {
+                funder.require_auth();
+            }
+            
+            open_escrow(
+                &env,
+                escrow_id,
+                job_id,
+                employer,
+                employee,
+                token,
+                amount,
+                deadline,
+                EscrowKind::Standard,
+                Some(funder),
+            )
+        }
+        
+        /// Create an hourly escrow that locks `cap` and pays approved timesheet entries
+        pub fn create_hourly_escrow(
+            env: Env,
+            escrow_id: String,
+            job_id: String,
+            employer: Address,
+            employee: Address,
+            token: Address,
+            rate_per_hour: i128,
+            cap: i128,
+            deadline: Option<u64>,
+        ) -> Escrow {
+            // Verify caller is the employer
+            employer.require_auth();
+            
+            assert!(rate_per_hour > 0, "Rate must be positive");
+            
+            let escrow = open_escrow(
+                &env,
+                escrow_id.clone(),
+                job_id,
+                employer,
+                Some(employee),
+                token,
+                cap,
+                deadline,
+                EscrowKind::Hourly,
+                None,
+            );
+            
+            env.storage()
+                .instance()
+                .set(&DataKey::HourlyRate(escrow_id), &rate_per_hour);
+            
+            escrow
+        }
+        
+        /// Create a streaming escrow whose amount vests linearly from `start` to `end`
+        /// An optional cliff holds back everything until that timestamp
+        pub fn create_stream_escrow(
+            env: Env,
+            escrow_id: String,
+            job_id: String,
+            employer: Address,
+            employee: Address,
+            token: Address,
+            amount: i128,
+            start: u64,
+            end: u64,
+            cliff: Option<u64>,
+        ) -> Escrow {
+            // Verify caller is the employer
+            employer.require_auth();
+            
+            assert!(end > start, "Invalid schedule");
+            if let Some(cliff) = cliff {
+                assert!(cliff >= start && cliff <= end, "Cliff must be within the schedule");
+            }
+            
+            let escrow = open_escrow(
+                &env,
+                escrow_id.clone(),
+                job_id,
+                employer,
+                Some(employee),
+                token,
+                amount,
+                None,
+                EscrowKind::Stream,
+                None,
+            );
+            
+            env.storage()
+                .instance()
+                .set(&DataKey::Stream(escrow_id), &StreamSchedule { start, cliff, end });
+            
+            escrow
+        }
+        
+        /// Create an escrow worth `value` in the oracle's base currency (e.g. USD),
+        /// collateralized with `collateral` of a volatile token (e.g. XLM)
+        /// On approval the employee gets `value` at the current price and the rest is refunded
+        pub fn create_pegged_escrow(
+            env: Env,
+            escrow_id: String,
+            job_id: String,
+            employer: Address,
+            employee: Address,
+            token: Address,
+            collateral: i128,
+            oracle: Address,
+            value: i128,
+            deadline: Option<u64>,
+        ) -> Escrow {
+            // Verify caller is the employer
+            employer.require_auth();
+            
+            assert!(value > 0, "Value must be positive");
+            
+            let escrow = open_escrow(
+                &env,
+                escrow_id.clone(),
+                job_id,
+                employer,
+                Some(employee),
+                token,
+                collateral,
+                deadline,
+                EscrowKind::Pegged,
+                None,
+            );
+            
+            env.storage()
+                .instance()
+                .set(&DataKey::Peg(escrow_id), &Peg { oracle, value });
+            
+            // Collateral has to cover the value at today's price
+            pegged_payout(&env, &escrow);
+            
+            escrow
+        }
+    }
+}
+
+// Leave the employer's review and tip through the configured contracts
+fn send_feedback(env: &Env, escrow: &Escrow, feedback: Feedback) {
+    let employee = escrow.employee.clone().expect("No employee assigned");
+    
+    let review_contract: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::ReviewContract)
+        .expect("Review contract not configured");
+    ReviewClient::new(env, &review_contract).leave_review(
+        &escrow.job_id,
+        &escrow.employer,
+        &employee,
+        &feedback.rating,
+        &feedback.comment,
+    );
+    
+    if feedback.tip_amount > 0 {
+        let tipping_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::TippingContract)
+            .expect("Tipping contract not configured");
+        TippingClient::new(env, &tipping_contract).send_tip(
+            &escrow.job_id,
+            &escrow.employer,
+            &employee,
+            &escrow.token,
+            &feedback.tip_amount,
+            &String::from_str(env, ""),
+        );
+    }
+}
+
+// Domain-separated message for a signed approval:
+// tag || contract address || escrow ID || nonce
+fn approval_message(env: &Env, escrow_id: &String, nonce: u64) -> Bytes {
+    let mut message = Bytes::from_slice(env, APPROVAL_DOMAIN);
+    message.append(&env.current_contract_address().to_xdr(env));
+    message.append(&escrow_id.clone().to_xdr(env));
+    message.extend_from_array(&nonce.to_be_bytes());
+    message
+}
+
+// Open a new escrow to first-come claims by any worker
+fn allow_claims(env: &Env, escrow: &mut Escrow) {
+    assert!(escrow.employee.is_none(), "Employee already assigned");
+    escrow.claimable = true;
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrow(escrow.id.clone()), escrow);
+}
+
+// Validate, fund and index a new escrow
+#[allow(clippy::too_many_arguments)]
+fn open_escrow(
+    env: &Env,
+    escrow_id: String,
+    job_id: String,
+    employer: Address,
+    employee: Option<Address>,
+    token: Address,
+    amount: i128,
+    deadline: Option<u64>,
+    kind: EscrowKind,
+    funder: Option<Address>,
+) -> Escrow {
+    // Validate amount is positive
+    assert!(amount > 0, "Amount must be positive");
+    
+    // Don't allow self-escrow
+    if let Some(employee) = &employee {
+        assert!(employer != *employee, "Cannot escrow to yourself");
+    }
+    
+    // Check if escrow already exists
+    let existing_escrow: Option<Escrow> = env
+        .storage()
+        .instance()
+        .get(&DataKey::Escrow(escrow_id.clone()));
+    
+    assert!(existing_escrow.is_none(), "Escrow already exists");
+    
+    // Only one active escrow per job
+    let job_key = DataKey::JobEscrow(job_id.clone());
+    if let Some(job_escrow_id) = env.storage().instance().get::<_, String>(&job_key) {
+        let job_escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(job_escrow_id))
+            .expect("Escrow not found");
+        assert!(job_escrow.status != EscrowStatus::Locked, "Job already has an active escrow");
+    }
+    
+    // Transfer tokens from employer to this contract (lock them),
+    // or pull them from the funder's allowance
+    let token_client = token::Client::new(env, &token);
+    let contract = env.current_contract_address();
+    let funder = match funder {
+        Some(funder) => {
+            token_client.transfer_from(&contract, &funder, &contract, &amount);
+            funder
+        }
+        None => {
+            token_client.transfer(&employer, &contract, &amount);
+            employer.clone()
+        }
+    };
+    
+    // Create escrow record
+    let escrow = Escrow {
+        id: escrow_id.clone(),
+        job_id,
+        employer: employer.clone(),
+        funder,
+        employee: employee.clone(),
+        amount,
+        released: 0,
+        token,
+        status: EscrowStatus::Locked,
+        created_at: env.ledger().timestamp(),
+        deadline,
+        kind,
+        amendments: Vec::new(env),
+        approvers: Vec::new(env),
+        threshold: 0,
+        approvals: Vec::new(env),
+        approved_at: None,
+        closed_at: None,
+        claimable: false,
+    };
+    
+    // Store escrow
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrow(escrow_id.clone()), &escrow);
+    
+    // Point the job at its newest escrow
+    env.storage().instance().set(&job_key, &escrow_id);
+    
+    stats_lock(env, &escrow, amount);
+    stats_status(env, &escrow);
+    log_history(env, &escrow_id, escrow.employer.clone(), EscrowAction::Created, amount);
+    
+    // Add to employer's list
+    let mut employer_escrows: Vec<String> = env
+        .storage()
+        .instance()
+        .get(&DataKey::EmployerEscrows(employer.clone()))
+        .unwrap_or(Vec::new(env));
+    employer_escrows.push_back(escrow_id.clone());
+    env.storage()
+        .instance()
+        .set(&DataKey::EmployerEscrows(employer.clone()), &employer_escrows);
+    append_id(env, DataKey::EmployerStatusEscrows(employer, EscrowStatus::Locked), escrow_id.clone());
+    
+    // Add to employee's list (open escrows are indexed on assignment)
+    if let Some(employee) = employee {
+        index_employee_escrow(env, employee, escrow_id);
+    }
+    
+    escrow
+}
+
+// Pay `amount` out of the contract to the escrow's employee, or split it across a team
+// Team splits round down; the leftover dust goes to the first payee
+fn release_funds(env: &Env, escrow: &Escrow, amount: i128) {
+    let token_client = token::Client::new(env, &escrow.token);
+    stats_unlock(env, escrow, amount);
+    
+    if escrow.kind == EscrowKind::Team {
+        let shares: Vec<Share> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TeamShares(escrow.id.clone()))
+            .expect("Team shares not found");
+        
+        let mut paid: i128 = 0;
+        let mut cuts: Vec<i128> = Vec::new(env);
+        for share in shares.iter() {
+            let cut = amount * share.bps as i128 / 10_000;
+            cuts.push_back(cut);
+            paid += cut;
+        }
+        
+        for (i, share) in shares.iter().enumerate() {
+            let mut cut = cuts.get(i as u32).unwrap_or(0);
+            if i == 0 {
+                cut += amount - paid;
+            }
+            if cut > 0 {
+                token_client.transfer(&env.current_contract_address(), &share.payee, &cut);
+                stats_release(env, escrow, &share.payee, cut);
+            }
+        }
+        return;
+    }
+    
+    // Open escrows can't be released until someone is assigned
+    let employee = escrow.employee.clone().expect("No employee assigned");
+    token_client.transfer(&env.current_contract_address(), &employee, &amount);
+    stats_release(env, escrow, &employee, amount);
+}
+
+// Amount of a streaming escrow vested at the current ledger time
+// Frozen once the stream is no longer locked
+fn vested_amount(env: &Env, escrow: &Escrow) -> i128 {
+    let schedule: StreamSchedule = env
+        .storage()
+        .instance()
+        .get(&DataKey::Stream(escrow.id.clone()))
+        .expect("Not a streaming escrow");
+    
+    if escrow.status != EscrowStatus::Locked {
+        return escrow.released;
+    }
+    
+    let now = env.ledger().timestamp();
+    if now < schedule.cliff.unwrap_or(schedule.start) || now <= schedule.start {
+        return 0;
+    }
+    if now >= schedule.end {
+        return escrow.amount;
+    }
+    
+    let elapsed = (now - schedule.start) as i128;
+    let duration = (schedule.end - schedule.start) as i128;
+    escrow.amount * elapsed / duration
+}
+
+// Tokens needed to pay a pegged escrow's value at the oracle's latest price
+fn pegged_payout(env: &Env, escrow: &Escrow) -> i128 {
+    let peg: Peg = env
+        .storage()
+        .instance()
+        .get(&DataKey::Peg(escrow.id.clone()))
+        .expect("Not a pegged escrow");
+    
+    let oracle = PriceOracleClient::new(env, &peg.oracle);
+    let price = oracle
+        .lastprice(&Asset::Stellar(escrow.token.clone()))
+        .expect("No price available");
+    assert!(price.price > 0, "Invalid price");
+    assert!(
+        price.timestamp + MAX_PRICE_AGE >= env.ledger().timestamp(),
+        "Stale price"
+    );
+    
+    // `value` and `price` share the oracle's decimals, so only token decimals remain
+    let token_decimals = token::Client::new(env, &escrow.token).decimals();
+    let payout = peg.value * 10i128.pow(token_decimals) / price.price;
+    assert!(payout <= escrow.amount, "Insufficient collateral");
+    payout
+}
+
+// Send the unforfeited part of a posted bond back to the employee
+fn return_bond(env: &Env, escrow: &Escrow) {
+    let key = DataKey::Bond(escrow.id.clone());
+    let mut bond: Bond = match env.storage().instance().get(&key) {
+        Some(bond) => bond,
+        None => return,
+    };
+    if !bond.posted || bond.returned {
+        return;
+    }
+    
+    let remaining = bond.amount - bond.forfeited;
+    if remaining > 0 {
+        let employee = escrow.employee.clone().expect("No employee assigned");
+        let token_client = token::Client::new(env, &bond.token);
+        token_client.transfer(&env.current_contract_address(), &employee, &remaining);
+    }
+    
+    bond.returned = true;
+    env.storage().instance().set(&key, &bond);
+}
+
+// Apply new terms to an escrow, log the change and save it
+fn record_amendment(
+    env: &Env,
+    escrow: &mut Escrow,
+    kind: AmendmentKind,
+    new_amount: i128,
+    new_deadline: Option<u64>,
+) {
+    escrow.amendments.push_back(Amendment {
+        kind,
+        prev_amount: escrow.amount,
+        new_amount,
+        prev_deadline: escrow.deadline,
+        new_deadline,
+        timestamp: env.ledger().timestamp(),
+    });
+    escrow.amount = new_amount;
+    escrow.deadline = new_deadline;
+    log_history(env, &escrow.id, escrow.employer.clone(), EscrowAction::Amended, new_amount);
+    
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrow(escrow.id.clone()), escrow);
+}
+
+// Fill the employee slot of an open escrow and index it
+fn set_employee(env: &Env, escrow: &mut Escrow, employee: Address) {
+    assert!(escrow.status == EscrowStatus::Locked, "Escrow must be locked");
+    assert!(
+        matches!(escrow.kind, EscrowKind::Standard | EscrowKind::Pegged),
+        "Escrow has no single employee"
+    );
+    assert!(escrow.employee.is_none(), "Employee already assigned");
+    assert!(escrow.employer != employee, "Cannot escrow to yourself");
+    
+    escrow.employee = Some(employee.clone());
+    env.storage()
+        .instance()
+        .set(&DataKey::Escrow(escrow.id.clone()), escrow);
+    
+    // The employee now shares in what's still locked
+    stats_join(env, escrow, employee.clone());
+    
+    index_employee_escrow(env, employee, escrow.id.clone());
+    record_activity(env, escrow.id.clone());
+}
+
+// Append an entry to an escrow's activity log
+fn log_history(env: &Env, escrow_id: &String, actor: Address, action: EscrowAction, amount: i128) {
+    let key = DataKey::History(escrow_id.clone());
+    let mut history: Vec<HistoryEntry> = env.storage().instance().get(&key).unwrap_or(Vec::new(env));
+    history.push_back(HistoryEntry {
+        timestamp: env.ledger().timestamp(),
+        actor,
+        action,
+        amount,
+    });
+    env.storage().instance().set(&key, &history);
+}
+
+// Note that the employee just acted on an escrow
+fn record_activity(env: &Env, escrow_id: String) {
+    env.storage()
+        .instance()
+        .set(&DataKey::LastActivity(escrow_id), &env.ledger().timestamp());
+}
+
+// Add an escrow ID to an employee's list
+// Employees are only ever added while the escrow is still locked
+fn index_employee_escrow(env: &Env, employee: Address, escrow_id: String) {
+    let mut employee_escrows: Vec<String> = env
+        .storage()
+        .instance()
+        .get(&DataKey::EmployeeEscrows(employee.clone()))
+        .unwrap_or(Vec::new(env));
+    employee_escrows.push_back(escrow_id.clone());
+    env.storage()
+        .instance()
+        .set(&DataKey::EmployeeEscrows(employee.clone()), &employee_escrows);
+    append_id(env, DataKey::EmployeeStatusEscrows(employee, EscrowStatus::Locked), escrow_id);
+}
+
+// Append an escrow ID to a stored list
+fn append_id(env: &Env, key: DataKey, escrow_id: String) {
+    let mut ids: Vec<String> = env.storage().instance().get(&key).unwrap_or(Vec::new(env));
+    ids.push_back(escrow_id);
+    env.storage().instance().set(&key, &ids);
+}
+
+// Drop an escrow ID from a stored list
+fn remove_id(env: &Env, key: DataKey, escrow_id: &String) {
+    let mut ids: Vec<String> = env.storage().instance().get(&key).unwrap_or(Vec::new(env));
+    if let Some(i) = ids.first_index_of(escrow_id) {
+        ids.remove(i);
+    }
+    env.storage().instance().set(&key, &ids);
+}
+
+// Everyone an escrow is listed for on the employee side
+fn escrow_payees(env: &Env, escrow: &Escrow) -> Vec<Address> {
+    let mut payees = Vec::new(env);
+    match escrow.kind {
+        EscrowKind::Team => {
+            let shares: Vec<Share> = env
+                .storage()
+                .instance()
+                .get(&DataKey::TeamShares(escrow.id.clone()))
+                .unwrap_or(Vec::new(env));
+            for share in shares.iter() {
+                payees.push_back(share.payee);
+            }
+        }
+        EscrowKind::Bounty => {
+            let winners: Vec<Payout> = env
+                .storage()
+                .instance()
+                .get(&DataKey::BountyWinners(escrow.id.clone()))
+                .unwrap_or(Vec::new(env));
+            for winner in winners.iter() {
+                payees.push_back(winner.to);
+            }
+        }
+        _ => {
+            if let Some(employee) = &escrow.employee {
+                payees.push_back(employee.clone());
+            }
+        }
+    }
+    payees
+}
+
+// Record an escrow leaving the locked state in its timestamps, history,
+// stats and status indexes, and drop any settlement still pending
+fn record_status(env: &Env, escrow: &mut Escrow, actor: Address) {
+    let now = env.ledger().timestamp();
+    escrow.closed_at = Some(now);
+    let action = match escrow.status {
+        EscrowStatus::Approved => {
+            escrow.approved_at = Some(now);
+            EscrowAction::Approved
+        }
+        EscrowStatus::Cancelled => EscrowAction::Cancelled,
+        EscrowStatus::Settled => EscrowAction::Settled,
+        EscrowStatus::Locked => panic!("Escrow still locked"),
+    };
+    log_history(env, &escrow.id, actor, action, escrow.released);
+    
+    stats_status(env, escrow);
+    
+    let from = EscrowStatus::Locked;
+    remove_id(env, DataKey::EmployerStatusEscrows(escrow.employer.clone(), from.clone()), &escrow.id);
+    append_id(env, DataKey::EmployerStatusEscrows(escrow.employer.clone(), escrow.status.clone()), escrow.id.clone());
+    for payee in escrow_payees(env, escrow).iter() {
+        remove_id(env, DataKey::EmployeeStatusEscrows(payee.clone(), from.clone()), &escrow.id);
+        append_id(env, DataKey::EmployeeStatusEscrows(payee, escrow.status.clone()), escrow.id.clone());
+    }
+    
+    env.storage()
+        .instance()
+        .remove(&DataKey::Settlement(escrow.id.clone()));
+}
+
+// Load a page of escrows from a status index, optionally only in one token
+fn list_escrows(env: &Env, key: DataKey, token: Option<Address>, start: u32, limit: u32) -> Vec<Escrow> {
+    let ids: Vec<String> = env.storage().instance().get(&key).unwrap_or(Vec::new(env));
+    
+    let mut escrows = Vec::new(env);
+    let mut skipped = 0;
+    for id in ids.iter() {
+        if escrows.len() >= limit {
+            break;
+        }
+        let escrow: Escrow = env
+            .storage()
+            .instance()
+            .get(&DataKey::Escrow(id))
+            .expect("Escrow not found");
+        if token.as_ref().is_some_and(|token| *token != escrow.token) {
+            continue;
+        }
+        if skipped < start {
+            skipped += 1;
+            continue;
+        }
+        escrows.push_back(escrow);
+    }
+    escrows
+}
+
+// Load a user's stats for a token, starting from zero
+fn load_stats(env: &Env, key: &DataKey) -> EscrowStats {
+    env.storage().instance().get(key).unwrap_or_default()
+}
+
+// Apply a change to the stats of the employer and everyone the escrow pays
+fn update_stats(env: &Env, escrow: &Escrow, change: impl Fn(&mut EscrowStats)) {
+    let key = DataKey::EmployerStats(escrow.employer.clone(), escrow.token.clone());
+    let mut stats = load_stats(env, &key);
+    change(&mut stats);
+    env.storage().instance().set(&key, &stats);
+    
+    for payee in escrow_payees(env, escrow).iter() {
+        let key = DataKey::EmployeeStats(payee, escrow.token.clone());
+        let mut stats = load_stats(env, &key);
+        change(&mut stats);
+        env.storage().instance().set(&key, &stats);
+    }
+}
+
+// Count a locked escrow for a payee added after it was created
+fn stats_join(env: &Env, escrow: &Escrow, payee: Address) {
+    let key = DataKey::EmployeeStats(payee, escrow.token.clone());
+    let mut stats = load_stats(env, &key);
+    stats.locked += escrow.amount - escrow.released;
+    stats.locked_count += 1;
+    env.storage().instance().set(&key, &stats);
+}
+
+// Count newly locked funds for both parties
+fn stats_lock(env: &Env, escrow: &Escrow, amount: i128) {
+    update_stats(env, escrow, |stats| stats.locked += amount);
+}
+
+// Move paid out or refunded funds out of both parties' locked totals
+fn stats_unlock(env: &Env, escrow: &Escrow, amount: i128) {
+    update_stats(env, escrow, |stats| stats.locked -= amount);
+}
+
+// Move refunded funds out of both parties' locked totals
+// Only the employer's side counts them as refunded
+fn stats_refund(env: &Env, escrow: &Escrow, amount: i128) {
+    stats_unlock(env, escrow, amount);
+    
+    let key = DataKey::EmployerStats(escrow.employer.clone(), escrow.token.clone());
+    let mut stats = load_stats(env, &key);
+    stats.refunded += amount;
+    env.storage().instance().set(&key, &stats);
+}
+
+// Record a payout to `payee` as released by the employer
+// The locked totals are updated separately with `stats_unlock`
+fn stats_release(env: &Env, escrow: &Escrow, payee: &Address, amount: i128) {
+    let key = DataKey::EmployerStats(escrow.employer.clone(), escrow.token.clone());
+    let mut stats = load_stats(env, &key);
+    stats.released += amount;
+    env.storage().instance().set(&key, &stats);
+    
+    let key = DataKey::EmployeeStats(payee.clone(), escrow.token.clone());
+    let mut stats = load_stats(env, &key);
+    stats.released += amount;
+    env.storage().instance().set(&key, &stats);
+}
+
+// Count a new escrow, or move a settled one out of the locked count
+fn stats_status(env: &Env, escrow: &Escrow) {
+    update_stats(env, escrow, |stats| match escrow.status {
+        EscrowStatus::Locked => stats.locked_count += 1,
+        EscrowStatus::Approved => {
+            stats.locked_count -= 1;
+            stats.approved_count += 1;
+        }
+        EscrowStatus::Cancelled => {
+            stats.locked_count -= 1;
+            stats.cancelled_count += 1;
+        }
+        EscrowStatus::Settled => {
+            stats.locked_count -= 1;
+            stats.settled_count += 1;
+        }
+    });
+}
+
+// Build a contract-assigned escrow ID like "escrow-42"
+fn generated_id(env: &Env, counter: u64) -> String {
+    let prefix = b"escrow-";
+    let mut buf = [0u8; 27];
+    buf[..prefix.len()].copy_from_slice(prefix);
+    
+    // Write the decimal digits of the counter after the prefix
+    let mut digits = [0u8; 20];
+    let mut n = counter;
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (n % 10) as u8;
+        len += 1;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    for (i, digit) in digits[..len].iter().rev().enumerate() {
+        buf[prefix.len() + i] = *digit;
+    }
+    
+    String::from_bytes(env, &buf[..prefix.len() + len])
+}
+
 #[cfg(test)]
 mod test;
 
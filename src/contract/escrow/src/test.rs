@@ -1,43 +1,2570 @@
 #![cfg(test)]
+extern crate std;
 
 use super::*;
-use soroban_sdk::testutils::{Ledger, LedgerInfo};
+use ed25519_dalek::{Signer, SigningKey};
+use soroban_sdk::testutils::{Ledger, MockAuth, MockAuthInvoke};
 use soroban_sdk::{
-    testutils::Address as TestAddress, Address, Bytes, String, Vec
+    testutils::Address as _, token, vec, Address, BytesN, IntoVal, String
 };
 
+// Mock token contract for testing
+fn create_token_contract<'a>(e: &Env, admin: &Address) -> (Address, token::Client<'a>, token::StellarAssetClient<'a>) {
+    let contract_address = e.register_stellar_asset_contract_v2(admin.clone());
+    let contract_addr = contract_address.address();
+    (
+        contract_addr.clone(),
+        token::Client::new(e, &contract_addr),
+        token::StellarAssetClient::new(e, &contract_addr),
+    )
+}
+
 #[test]
 fn test_create_escrow() {
     let env = Env::default();
+    env.mock_all_auths();
     
     // Create test addresses
-    let employer = TestAddress::generate(&env);
-    let employee = TestAddress::generate(&env);
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
     
     // Create token
-    let token = TestAddress::generate(&env);
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
     
     // Set ledger info
-    env.ledger().set(LedgerInfo {
-        timestamp: 123456,
-        protocol_version: 0,
-        sequence_number: 0,
-        base_reserve: 100,
-        network_passphrase: String::from_str(&env, "Test SDF Network"),
-        base_fee: 100,
-    });
+    env.ledger().with_mut(|li| li.timestamp = 123456);
     
     // Create contract
-    let contract_id = env.register_contract(None, EscrowContract);
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
     let client = EscrowContractClient::new(&env, &contract_id);
     
-    // Mock token allowance
     let escrow_id = String::from_str(&env, "test_escrow");
     let job_id = String::from_str(&env, "test_job");
     
-    // Note: In real tests, you'd need to mock the token contract
-    // and set up proper allowances. This is a simplified version.
+    let escrow = client.create_escrow(
+        &escrow_id,
+        &job_id,
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    assert_eq!(escrow.status, EscrowStatus::Locked);
+    assert_eq!(escrow.created_at, 123456);
+    assert_eq!(token_client.balance(&contract_id), 100_0000000);
+    assert_eq!(token_client.balance(&employer), 900_0000000);
+    assert!(client.is_locked(&escrow_id));
+}
+
+#[test]
+fn test_create_escrow_auto_id() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    // Caller-supplied ID that collides with the first generated one is skipped
+    client.create_escrow(
+        &String::from_str(&env, "escrow-0"),
+        &String::from_str(&env, "job-0"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &10_0000000,
+        &None
+    );
+    
+    let first = client.create_escrow_auto(
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &10_0000000,
+        &None
+    );
+    let second = client.create_escrow_auto(
+        &String::from_str(&env, "job-2"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &10_0000000,
+        &None
+    );
+    
+    assert_eq!(first.id, String::from_str(&env, "escrow-1"));
+    assert_eq!(second.id, String::from_str(&env, "escrow-2"));
+    assert_eq!(client.get_employer_escrows(&employer).len(), 3);
+}
+
+#[test]
+fn test_get_escrow_by_job() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let job_id = String::from_str(&env, "job-1");
+    let escrow = client.create_escrow_auto(
+        &job_id,
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &10_0000000,
+        &None
+    );
+    assert_eq!(client.get_escrow_by_job(&job_id), escrow);
+    
+    // Once the first escrow is closed the job can be escrowed again
+    client.cancel_escrow(&escrow.id, &employer);
+    let replacement = client.create_escrow_auto(
+        &job_id,
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &20_0000000,
+        &None
+    );
+    assert_eq!(client.get_escrow_by_job(&job_id).id, replacement.id);
+}
+
+#[test]
+#[should_panic(expected = "Job already has an active escrow")]
+fn test_one_active_escrow_per_job() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let job_id = String::from_str(&env, "job-1");
+    client.create_escrow_auto(&job_id, &employer, &Some(employee.clone()), &token_id, &10_0000000, &None);
+    client.create_escrow_auto(&job_id, &employer, &Some(employee.clone()), &token_id, &10_0000000, &None);
+}
+
+#[test]
+fn test_open_escrow_assign_employee() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    // Funded at posting time with nobody assigned
+    let escrow = client.create_escrow_auto(
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &None,
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    assert_eq!(escrow.employee, None);
+    assert_eq!(token_client.balance(&contract_id), 100_0000000);
+    assert_eq!(client.get_employee_escrows(&employee).len(), 0);
+    
+    let assigned = client.assign_employee(&escrow.id, &employer, &employee);
+    assert_eq!(assigned.employee, Some(employee.clone()));
+    assert_eq!(client.get_employee_escrows(&employee).len(), 1);
+    
+    client.approve_escrow(&escrow.id, &employer, &None);
+    assert_eq!(token_client.balance(&employee), 100_0000000);
+}
+
+#[test]
+fn test_claim_open_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let worker = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow = client.create_claimable_escrow(
+        &String::from_str(&env, "escrow-1"),
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    let claimed = client.claim_escrow(&escrow.id, &worker);
+    assert_eq!(claimed.employee, Some(worker.clone()));
+    assert_eq!(client.get_employee_escrows(&worker).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Employee already assigned")]
+fn test_claim_assigned_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let latecomer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow = client.create_claimable_escrow(
+        &String::from_str(&env, "escrow-1"),
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.claim_escrow(&escrow.id, &employee);
+    client.claim_escrow(&escrow.id, &latecomer);
+}
+
+#[test]
+#[should_panic(expected = "Escrow not open to claims")]
+fn test_claim_unclaimable_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let worker = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    // Open job the employer means to assign themselves
+    let escrow = client.create_escrow_auto(
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &None,
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.claim_escrow(&escrow.id, &worker);
+}
+
+#[test]
+#[should_panic(expected = "No employee assigned")]
+fn test_approve_open_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow = client.create_escrow_auto(
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &None,
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.approve_escrow(&escrow.id, &employer, &None);
+}
+
+#[test]
+fn test_bounty_pick_winners() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let worker1 = Address::generate(&env);
+    let worker2 = Address::generate(&env);
+    let worker3 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "bounty-1");
+    let escrow = client.create_bounty(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &300_0000000,
+        &1000
+    );
+    assert_eq!(escrow.kind, EscrowKind::Bounty);
+    
+    client.submit_entry(&escrow_id, &worker1, &BytesN::from_array(&env, &[1; 32]));
+    client.submit_entry(&escrow_id, &worker2, &BytesN::from_array(&env, &[2; 32]));
+    client.submit_entry(&escrow_id, &worker3, &BytesN::from_array(&env, &[3; 32]));
+    // Resubmitting replaces the earlier entry
+    client.submit_entry(&escrow_id, &worker1, &BytesN::from_array(&env, &[4; 32]));
+    
+    let entries = client.get_bounty_entries(&escrow_id);
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries.get(0).unwrap().entry_hash, BytesN::from_array(&env, &[4; 32]));
+    
+    let winners = vec![
+        &env,
+        Payout { to: worker1.clone(), amount: 200_0000000 },
+        Payout { to: worker2.clone(), amount: 50_0000000 },
+    ];
+    client.pick_winners(&escrow_id, &employer, &winners);
+    
+    assert_eq!(token_client.balance(&worker1), 200_0000000);
+    assert_eq!(token_client.balance(&worker2), 50_0000000);
+    assert_eq!(token_client.balance(&worker3), 0);
+    // Unawarded remainder goes back to the employer
+    assert_eq!(token_client.balance(&employer), 750_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+    assert_eq!(client.get_bounty_winners(&escrow_id), winners);
+    assert_eq!(client.get_employee_escrows(&worker1).len(), 1);
+}
+
+#[test]
+#[should_panic(expected = "Winner has no entry")]
+fn test_bounty_winner_without_entry() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let worker = Address::generate(&env);
+    let outsider = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "bounty-1");
+    client.create_bounty(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &300_0000000,
+        &1000
+    );
+    client.submit_entry(&escrow_id, &worker, &BytesN::from_array(&env, &[1; 32]));
+    
+    client.pick_winners(
+        &escrow_id,
+        &employer,
+        &vec![&env, Payout { to: outsider, amount: 100_0000000 }]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Duplicate winner")]
+fn test_bounty_duplicate_winner() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let worker = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "bounty-1");
+    client.create_bounty(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &300_0000000,
+        &1000
+    );
+    client.submit_entry(&escrow_id, &worker, &BytesN::from_array(&env, &[1; 32]));
+    
+    client.pick_winners(
+        &escrow_id,
+        &employer,
+        &vec![
+            &env,
+            Payout { to: worker.clone(), amount: 100_0000000 },
+            Payout { to: worker, amount: 100_0000000 },
+        ]
+    );
+}
+
+#[test]
+fn test_bounty_refund_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let worker = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "bounty-1");
+    client.create_bounty(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &300_0000000,
+        &1000
+    );
+    client.submit_entry(&escrow_id, &worker, &BytesN::from_array(&env, &[1; 32]));
+    
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    client.refund_bounty(&escrow_id, &employer);
+    
+    assert_eq!(token_client.balance(&employer), 1000_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Cancelled);
+}
+
+#[test]
+#[should_panic(expected = "Bounty still open")]
+fn test_bounty_refund_before_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "bounty-1");
+    client.create_bounty(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &300_0000000,
+        &1000
+    );
+    client.refund_bounty(&escrow_id, &employer);
+}
+
+#[test]
+fn test_team_escrow_split() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let dev1 = Address::generate(&env);
+    let dev2 = Address::generate(&env);
+    let dev3 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "team-1");
+    let shares = vec![
+        &env,
+        Share { payee: dev1.clone(), bps: 3_334 },
+        Share { payee: dev2.clone(), bps: 3_333 },
+        Share { payee: dev3.clone(), bps: 3_333 },
+    ];
+    client.create_team_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &shares,
+        &token_id,
+        &100,
+        &None
+    );
+    
+    assert_eq!(client.get_team_shares(&escrow_id), shares);
+    assert_eq!(client.get_employee_escrows(&dev1).len(), 1);
+    assert_eq!(client.get_employee_escrows(&dev3).len(), 1);
+    
+    client.approve_escrow(&escrow_id, &employer, &None);
+    
+    // 33 + 33 + 33 with the 1 stroop of dust going to the first payee
+    assert_eq!(token_client.balance(&dev1), 34);
+    assert_eq!(token_client.balance(&dev2), 33);
+    assert_eq!(token_client.balance(&dev3), 33);
+    assert_eq!(token_client.balance(&contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Shares must total 10000 bps")]
+fn test_team_escrow_bad_shares() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let dev1 = Address::generate(&env);
+    let dev2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    client.create_team_escrow(
+        &String::from_str(&env, "team-1"),
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &vec![
+            &env,
+            Share { payee: dev1, bps: 5_000 },
+            Share { payee: dev2, bps: 4_000 },
+        ],
+        &token_id,
+        &100_0000000,
+        &None
+    );
+}
+
+#[test]
+fn test_top_up_and_extend_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &Some(1000)
+    );
+    
+    client.top_up_escrow(&escrow_id, &employer, &50_0000000);
+    let escrow = client.extend_deadline(&escrow_id, &employer, &2000);
+    
+    assert_eq!(escrow.amount, 150_0000000);
+    assert_eq!(escrow.deadline, Some(2000));
+    assert_eq!(escrow.amendments.len(), 2);
+    assert_eq!(escrow.amendments.get(0).unwrap().kind, AmendmentKind::TopUp);
+    assert_eq!(escrow.amendments.get(1).unwrap().prev_deadline, Some(1000));
+    assert_eq!(token_client.balance(&contract_id), 150_0000000);
+    
+    // Still approvable after the original deadline
+    env.ledger().with_mut(|li| li.timestamp = 1500);
+    client.approve_escrow(&escrow_id, &employer, &None);
+    assert_eq!(token_client.balance(&employee), 150_0000000);
+}
+
+#[test]
+fn test_mutual_amendment_refunds_difference() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &Some(1000)
+    );
+    
+    let escrow = client.amend_escrow(&escrow_id, &employer, &employee, &60_0000000, &None);
+    
+    assert_eq!(escrow.amount, 60_0000000);
+    assert_eq!(escrow.deadline, None);
+    assert_eq!(escrow.amendments.get(0).unwrap().kind, AmendmentKind::Mutual);
+    assert_eq!(token_client.balance(&employer), 940_0000000);
+    assert_eq!(token_client.balance(&contract_id), 60_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Deadline can only be extended")]
+fn test_extend_deadline_earlier() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee),
+        &token_id,
+        &100_0000000,
+        &Some(1000)
+    );
+    client.extend_deadline(&escrow_id, &employer, &500);
+}
+
+#[test]
+fn test_multisig_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let manager1 = Address::generate(&env);
+    let manager2 = Address::generate(&env);
+    let manager3 = Address::generate(&env);
+    let admin = Address::generate(&env);
     
-    // The actual test would require token mocking which is more complex
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.set_approvers(
+        &escrow_id,
+        &employer,
+        &vec![&env, manager1.clone(), manager2.clone(), manager3.clone()],
+        &2
+    );
+    
+    // First approval is recorded but nothing is released
+    client.approve_escrow(&escrow_id, &manager1, &None);
+    assert!(client.is_locked(&escrow_id));
+    assert_eq!(client.get_escrow(&escrow_id).approvals.len(), 1);
+    assert_eq!(token_client.balance(&employee), 0);
+    
+    // Second distinct approver meets the threshold
+    client.approve_escrow(&escrow_id, &manager3, &None);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+    assert_eq!(token_client.balance(&employee), 100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Already approved")]
+fn test_multisig_duplicate_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let manager1 = Address::generate(&env);
+    let manager2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.set_approvers(&escrow_id, &employer, &vec![&env, manager1.clone(), manager2], &2);
+    
+    client.approve_escrow(&escrow_id, &manager1, &None);
+    client.approve_escrow(&escrow_id, &manager1, &None);
+}
+
+// Sign the approval message for `nonce` the way an off-chain client would
+fn sign_approval(
+    env: &Env,
+    client: &EscrowContractClient,
+    key: &SigningKey,
+    escrow_id: &String,
+    nonce: u64,
+) -> BytesN<64> {
+    let message: std::vec::Vec<u8> = client.get_approval_message(escrow_id, &nonce).iter().collect();
+    BytesN::from_array(env, &key.sign(&message).to_bytes())
+}
+
+#[test]
+fn test_approve_with_signature() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    let key = SigningKey::from_bytes(&[7; 32]);
+    let public_key = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    client.register_signing_key(&escrow_id, &employer, &public_key);
+    
+    // A relayer submits the employer's signed approval
+    let signature = sign_approval(&env, &client, &key, &escrow_id, 0);
+    client.approve_with_signature(&escrow_id, &public_key, &signature, &0);
+    
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+    assert_eq!(token_client.balance(&employee), 100_0000000);
+    assert_eq!(client.get_approval_nonce(&escrow_id), 1);
+}
+
+#[test]
+#[should_panic(expected = "Invalid nonce")]
+fn test_approve_with_signature_replay() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let manager1 = Address::generate(&env);
+    let manager2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(Address::generate(&env)),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.set_approvers(&escrow_id, &employer, &vec![&env, manager1.clone(), manager2], &2);
+    
+    let key = SigningKey::from_bytes(&[9; 32]);
+    let public_key = BytesN::from_array(&env, &key.verifying_key().to_bytes());
+    client.register_signing_key(&escrow_id, &manager1, &public_key);
+    
+    let signature = sign_approval(&env, &client, &key, &escrow_id, 0);
+    client.approve_with_signature(&escrow_id, &public_key, &signature, &0);
+    client.approve_with_signature(&escrow_id, &public_key, &signature, &0);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_employer_cannot_bypass_approvers() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let manager = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.set_approvers(&escrow_id, &employer, &vec![&env, manager], &1);
+    
+    client.approve_escrow(&escrow_id, &employer, &None);
+}
+
+#[test]
+fn test_bond_returned_on_approval() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    token_admin.mint(&employee, &100_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &500_0000000,
+        &None
+    );
+    client.require_bond(&escrow_id, &employer, &token_id, &50_0000000, &arbiter);
+    client.post_bond(&escrow_id, &employee);
+    
+    assert_eq!(token_client.balance(&employee), 50_0000000);
+    assert!(client.get_bond(&escrow_id).unwrap().posted);
+    
+    client.approve_escrow(&escrow_id, &employer, &None);
+    
+    // Payment plus the full bond
+    assert_eq!(token_client.balance(&employee), 600_0000000);
+    assert!(client.get_bond(&escrow_id).unwrap().returned);
 }
 
+#[test]
+fn test_bond_partially_forfeited() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    token_admin.mint(&employee, &100_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &500_0000000,
+        &None
+    );
+    client.require_bond(&escrow_id, &employer, &token_id, &50_0000000, &arbiter);
+    client.post_bond(&escrow_id, &employee);
+    
+    let bond = client.forfeit_bond(&escrow_id, &arbiter, &20_0000000);
+    assert_eq!(bond.forfeited, 20_0000000);
+    assert_eq!(token_client.balance(&employer), 520_0000000);
+    
+    let history = client.get_escrow_history(&escrow_id);
+    let posted = history.get(1).unwrap();
+    assert_eq!((posted.action, posted.actor, posted.amount), (EscrowAction::BondPosted, employee.clone(), 50_0000000));
+    let forfeited = history.get(2).unwrap();
+    assert_eq!((forfeited.action, forfeited.actor, forfeited.amount), (EscrowAction::BondForfeited, arbiter, 20_0000000));
+    
+    // Cancelling refunds the escrow and returns the rest of the bond
+    client.cancel_escrow(&escrow_id, &employer);
+    assert_eq!(token_client.balance(&employer), 1020_0000000);
+    assert_eq!(token_client.balance(&employee), 80_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_only_arbiter_forfeits_bond() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    token_admin.mint(&employee, &100_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &500_0000000,
+        &None
+    );
+    client.require_bond(&escrow_id, &employer, &token_id, &50_0000000, &arbiter);
+    client.post_bond(&escrow_id, &employee);
+    
+    client.forfeit_bond(&escrow_id, &employer, &50_0000000);
+}
+
+#[test]
+fn test_hourly_escrow_timesheets() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    // 25 per hour, capped at 500
+    let escrow_id = String::from_str(&env, "hourly-1");
+    client.create_hourly_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &25_0000000,
+        &500_0000000,
+        &None
+    );
+    
+    let week1 = client.submit_timesheet(&escrow_id, &employee, &8, &0, &604800, &BytesN::from_array(&env, &[1; 32]));
+    let week2 = client.submit_timesheet(&escrow_id, &employee, &4, &604800, &1209600, &BytesN::from_array(&env, &[2; 32]));
+    
+    let approved = client.review_timesheet(&escrow_id, &employer, &week1, &true);
+    assert_eq!(approved.amount, 200_0000000);
+    assert_eq!(approved.status, TimesheetStatus::Approved);
+    assert_eq!(token_client.balance(&employee), 200_0000000);
+    
+    let rejected = client.review_timesheet(&escrow_id, &employer, &week2, &false);
+    assert_eq!(rejected.status, TimesheetStatus::Rejected);
+    assert_eq!(token_client.balance(&employee), 200_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).released, 200_0000000);
+    let logged = client.get_escrow_history(&escrow_id).last().unwrap();
+    assert_eq!((logged.action, logged.amount), (EscrowAction::TimesheetRejected, rejected.amount));
+    
+    // Unused balance goes back to the employer at close
+    let unused = client.close_hourly_escrow(&escrow_id, &employer);
+    assert_eq!(unused, 300_0000000);
+    assert_eq!(token_client.balance(&employer), 800_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+}
+
+#[test]
+#[should_panic(expected = "Entry exceeds remaining balance")]
+fn test_hourly_entry_over_cap() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "hourly-1");
+    client.create_hourly_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &25_0000000,
+        &100_0000000,
+        &None
+    );
+    
+    let entry = client.submit_timesheet(&escrow_id, &employee, &5, &0, &3600, &BytesN::from_array(&env, &[1; 32]));
+    client.review_timesheet(&escrow_id, &employer, &entry, &true);
+}
+
+#[test]
+#[should_panic(expected = "Timesheet entries pending review")]
+fn test_hourly_close_with_pending_entries() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "hourly-1");
+    client.create_hourly_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &25_0000000,
+        &500_0000000,
+        &None
+    );
+    
+    client.submit_timesheet(&escrow_id, &employee, &2, &0, &3600, &BytesN::from_array(&env, &[1; 32]));
+    client.close_hourly_escrow(&escrow_id, &employer);
+}
+
+#[test]
+fn test_stream_withdraw_vested() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "stream-1");
+    client.create_stream_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &400_0000000,
+        &1000,
+        &5000,
+        &None
+    );
+    
+    // A quarter of the way through
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    assert_eq!(client.get_vested_amount(&escrow_id), 100_0000000);
+    assert_eq!(client.withdraw_vested(&escrow_id, &employee), 100_0000000);
+    
+    // Three quarters
+    env.ledger().with_mut(|li| li.timestamp = 4000);
+    assert_eq!(client.withdraw_vested(&escrow_id, &employee), 200_0000000);
+    
+    // Fully vested after the end
+    env.ledger().with_mut(|li| li.timestamp = 6000);
+    assert_eq!(client.withdraw_vested(&escrow_id, &employee), 100_0000000);
+    
+    assert_eq!(token_client.balance(&employee), 400_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+}
+
+#[test]
+fn test_stream_cancel_splits_vested() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "stream-1");
+    client.create_stream_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &400_0000000,
+        &1000,
+        &5000,
+        &None
+    );
+    
+    env.ledger().with_mut(|li| li.timestamp = 2000);
+    client.withdraw_vested(&escrow_id, &employee);
+    
+    // Halfway: 200 vested, 100 of it already withdrawn
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    client.cancel_escrow(&escrow_id, &employer);
+    
+    assert_eq!(token_client.balance(&employee), 200_0000000);
+    assert_eq!(token_client.balance(&employer), 800_0000000);
+    assert_eq!(token_client.balance(&contract_id), 0);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Cancelled);
+}
+
+#[test]
+#[should_panic(expected = "Nothing vested")]
+fn test_stream_withdraw_before_cliff() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "stream-1");
+    client.create_stream_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &400_0000000,
+        &1000,
+        &5000,
+        &Some(3000)
+    );
+    
+    env.ledger().with_mut(|li| li.timestamp = 2500);
+    client.withdraw_vested(&escrow_id, &employee);
+}
+
+#[test]
+fn test_retainer_renews_each_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "retainer-1");
+    client.create_retainer_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &100_0000000,
+        &2_592_000
+    );
+    
+    // Allow the contract to pull two more periods
+    token_client.approve(&employer, &contract_id, &200_0000000, &1000);
+    
+    // Period 0 approved by the employer
+    assert_eq!(client.approve_period(&escrow_id, &employer), 100_0000000);
+    env.ledger().with_mut(|li| li.timestamp = 2_592_000);
+    let terms = client.roll_period(&escrow_id);
+    assert_eq!(terms.period, 1);
+    assert_eq!(token_client.balance(&employer), 800_0000000);
+    let renewed = client.get_escrow_history(&escrow_id).last().unwrap();
+    assert_eq!((renewed.action, renewed.amount), (EscrowAction::Renewed, 100_0000000));
+    
+    // Period 1 never approved, paid on timeout at the next roll
+    env.ledger().with_mut(|li| li.timestamp = 5_184_000);
+    let terms = client.roll_period(&escrow_id);
+    assert_eq!(terms.period, 2);
+    assert_eq!(token_client.balance(&employee), 200_0000000);
+    
+    // Allowance is used up, so the last roll pays out and completes
+    env.ledger().with_mut(|li| li.timestamp = 7_776_000);
+    let terms = client.roll_period(&escrow_id);
+    assert!(!terms.renew);
+    assert_eq!(token_client.balance(&employee), 300_0000000);
+    assert_eq!(token_client.balance(&employer), 700_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+}
+
+#[test]
+fn test_cancel_retainer_after_period_ends() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "retainer-1");
+    client.create_retainer_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &100_0000000,
+        &2_592_000
+    );
+    
+    // The period ran out unapproved, so cancelling can't claw it back
+    env.ledger().with_mut(|li| li.timestamp = 2_592_000);
+    client.cancel_escrow(&escrow_id, &employer);
+    assert_eq!(token_client.balance(&employee), 100_0000000);
+    assert_eq!(token_client.balance(&employer), 900_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Cancelled);
+}
+
+#[test]
+fn test_cancel_retainer_mid_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "retainer-1");
+    client.create_retainer_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &100_0000000,
+        &2_592_000
+    );
+    
+    // Before the period ends the unapproved amount is still refundable
+    env.ledger().with_mut(|li| li.timestamp = 1_000_000);
+    client.cancel_escrow(&escrow_id, &employer);
+    assert_eq!(token_client.balance(&employee), 0);
+    assert_eq!(token_client.balance(&employer), 1000_0000000);
+}
+
+#[test]
+fn test_retainer_stop_renewal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "retainer-1");
+    client.create_retainer_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &100_0000000,
+        &2_592_000
+    );
+    token_client.approve(&employer, &contract_id, &500_0000000, &1000);
+    
+    client.stop_renewal(&escrow_id, &employer);
+    let logged = client.get_escrow_history(&escrow_id).last().unwrap();
+    assert_eq!((logged.action, logged.actor), (EscrowAction::RenewalStopped, employer.clone()));
+    
+    env.ledger().with_mut(|li| li.timestamp = 2_592_000);
+    client.roll_period(&escrow_id);
+    
+    assert_eq!(token_client.balance(&employee), 100_0000000);
+    assert_eq!(token_client.balance(&employer), 900_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Approved);
+}
+
+#[test]
+#[should_panic(expected = "Period not over")]
+fn test_retainer_roll_too_early() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "retainer-1");
+    client.create_retainer_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &100_0000000,
+        &2_592_000
+    );
+    client.roll_period(&escrow_id);
+}
+
+#[test]
+fn test_escrow_funded_from_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    token_client.approve(&employer, &contract_id, &100_0000000, &1000);
+    
+    let escrow = client.create_escrow_from_allowance(
+        &String::from_str(&env, "escrow-1"),
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    assert_eq!(escrow.funder, employer);
+    assert_eq!(token_client.balance(&contract_id), 100_0000000);
+    assert_eq!(token_client.allowance(&employer, &contract_id), 0);
+}
+
+#[test]
+#[should_panic(expected = "Error(Auth, InvalidAction)")]
+fn test_escrow_from_someone_elses_allowance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let attacker = Address::generate(&env);
+    let accomplice = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&treasury, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    // The treasury approved the contract for its own escrows
+    token_client.approve(&treasury, &contract_id, &300_0000000, &1000);
+    
+    // Only the attacker signs, so spending the treasury's allowance must fail
+    let escrow_id = String::from_str(&env, "escrow-1");
+    let job_id = String::from_str(&env, "job-1");
+    let employee: Option<Address> = Some(accomplice);
+    let deadline: Option<u64> = None;
+    env.mock_auths(&[MockAuth {
+        address: &attacker,
+        invoke: &MockAuthInvoke {
+            contract: &contract_id,
+            fn_name: "create_escrow_from_allowance",
+            args: (
+                escrow_id.clone(),
+                job_id.clone(),
+                attacker.clone(),
+                treasury.clone(),
+                employee.clone(),
+                token_id.clone(),
+                300_0000000i128,
+                deadline,
+            )
+                .into_val(&env),
+            sub_invokes: &[],
+        },
+    }]);
+    client.create_escrow_from_allowance(
+        &escrow_id,
+        &job_id,
+        &attacker,
+        &treasury,
+        &employee,
+        &token_id,
+        &300_0000000,
+        &deadline
+    );
+}
+
+#[test]
+fn test_sponsored_escrow_refunds_funder() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let treasury = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&treasury, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    token_client.approve(&treasury, &contract_id, &300_0000000, &1000);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    let escrow = client.create_escrow_from_allowance(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &treasury,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    assert_eq!(escrow.employer, employer);
+    assert_eq!(escrow.funder, treasury);
+    
+    // Top-ups also come out of the treasury allowance
+    client.top_up_escrow(&escrow_id, &employer, &50_0000000);
+    assert_eq!(token_client.balance(&treasury), 850_0000000);
+    
+    // The employer still controls the escrow but the refund goes to the treasury
+    client.cancel_escrow(&escrow_id, &employer);
+    assert_eq!(token_client.balance(&treasury), 1000_0000000);
+    assert_eq!(token_client.balance(&employer), 0);
+}
+
+// Stand-in for the ReviewContract
+#[contract]
+pub struct MockReviews;
+
+#[contractimpl]
+impl MockReviews {
+    pub fn leave_review(
+        env: Env,
+        job_id: String,
+        reviewer: Address,
+        reviewee: Address,
+        rating: u32,
+        comment: String,
+    ) -> u64 {
+        reviewer.require_auth();
+        env.storage().instance().set(&job_id, &(reviewee, rating, comment));
+        1
+    }
+    
+    pub fn get_review(env: Env, job_id: String) -> (Address, u32, String) {
+        env.storage().instance().get(&job_id).unwrap()
+    }
+}
+
+// Stand-in for the TippingContract
+#[contract]
+pub struct MockTipping;
+
+#[contractimpl]
+impl MockTipping {
+    pub fn send_tip(
+        env: Env,
+        _job_id: String,
+        from: Address,
+        to: Address,
+        token: Address,
+        amount: i128,
+        _message: String,
+    ) -> u64 {
+        from.require_auth();
+        token::Client::new(&env, &token).transfer(&from, &to, &amount);
+        1
+    }
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized")]
+fn test_set_integrations_not_admin() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let outsider = Address::generate(&env);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let reviews_id = env.register(MockReviews, ());
+    let tipping_id = env.register(MockTipping, ());
+    
+    // Only the admin set at deployment can point the escrow at other contracts
+    client.set_integrations(&outsider, &reviews_id, &tipping_id);
+}
+
+#[test]
+fn test_approve_with_feedback() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let reviews_id = env.register(MockReviews, ());
+    let tipping_id = env.register(MockTipping, ());
+    
+    client.set_integrations(&owner, &reviews_id, &tipping_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    let job_id = String::from_str(&env, "job-1");
+    client.create_escrow(
+        &escrow_id,
+        &job_id,
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    let comment = String::from_str(&env, "Great work");
+    client.approve_escrow(
+        &escrow_id,
+        &employer,
+        &Some(Feedback { rating: 5, comment: comment.clone(), tip_amount: 10_0000000 })
+    );
+    
+    // Payment, review and tip all land in the same call
+    assert_eq!(token_client.balance(&employee), 110_0000000);
+    let review = MockReviewsClient::new(&env, &reviews_id).get_review(&job_id);
+    assert_eq!(review, (employee, 5, comment));
+}
+
+#[test]
+#[should_panic(expected = "Review contract not configured")]
+fn test_approve_with_feedback_unconfigured() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(Address::generate(&env)),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.approve_escrow(
+        &escrow_id,
+        &employer,
+        &Some(Feedback { rating: 4, comment: String::from_str(&env, ""), tip_amount: 0 })
+    );
+}
+
+#[test]
+fn test_escrow_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let first = client.create_escrow_auto(
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    let second = client.create_escrow_auto(
+        &String::from_str(&env, "job-2"),
+        &employer,
+        &None,
+        &token_id,
+        &50_0000000,
+        &None
+    );
+    client.assign_employee(&second.id, &employer, &employee);
+    client.top_up_escrow(&first.id, &employer, &20_0000000);
+    
+    let stats = client.get_employer_stats(&employer, &token_id);
+    assert_eq!(stats.locked, 170_0000000);
+    assert_eq!(stats.locked_count, 2);
+    assert_eq!(client.get_employee_stats(&employee, &token_id).locked, 170_0000000);
+    
+    client.approve_escrow(&first.id, &employer, &None);
+    client.cancel_escrow(&second.id, &employer);
+    
+    let stats = client.get_employer_stats(&employer, &token_id);
+    assert_eq!(stats.locked, 0);
+    assert_eq!(stats.released, 120_0000000);
+    assert_eq!(stats.refunded, 50_0000000);
+    assert_eq!(stats.locked_count, 0);
+    assert_eq!(stats.approved_count, 1);
+    assert_eq!(stats.cancelled_count, 1);
+    
+    let stats = client.get_employee_stats(&employee, &token_id);
+    assert_eq!(stats.locked, 0);
+    assert_eq!(stats.released, 120_0000000);
+    assert_eq!(stats.refunded, 0);
+    assert_eq!(stats.approved_count, 1);
+}
+
+#[test]
+fn test_team_and_bounty_stats() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let dev1 = Address::generate(&env);
+    let dev2 = Address::generate(&env);
+    let winner = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    // Team payees share in the whole locked escrow
+    let team_id = String::from_str(&env, "team-1");
+    client.create_team_escrow(
+        &team_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &vec![
+            &env,
+            Share { payee: dev1.clone(), bps: 6_000 },
+            Share { payee: dev2.clone(), bps: 4_000 },
+        ],
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.top_up_escrow(&team_id, &employer, &50_0000000);
+    
+    let stats = client.get_employee_stats(&dev2, &token_id);
+    assert_eq!(stats.locked, 150_0000000);
+    assert_eq!(stats.locked_count, 1);
+    
+    client.approve_escrow(&team_id, &employer, &None);
+    
+    let stats = client.get_employee_stats(&dev2, &token_id);
+    assert_eq!(stats.locked, 0);
+    assert_eq!(stats.released, 60_0000000);
+    assert_eq!(stats.locked_count, 0);
+    assert_eq!(stats.approved_count, 1);
+    
+    // Bounty winners are counted once picked
+    let bounty_id = String::from_str(&env, "bounty-1");
+    client.create_bounty(
+        &bounty_id,
+        &String::from_str(&env, "job-2"),
+        &employer,
+        &token_id,
+        &200_0000000,
+        &1000
+    );
+    client.submit_entry(&bounty_id, &winner, &BytesN::from_array(&env, &[1; 32]));
+    client.pick_winners(
+        &bounty_id,
+        &employer,
+        &vec![&env, Payout { to: winner.clone(), amount: 150_0000000 }]
+    );
+    
+    let stats = client.get_employee_stats(&winner, &token_id);
+    assert_eq!(stats.locked, 0);
+    assert_eq!(stats.released, 150_0000000);
+    assert_eq!(stats.refunded, 0);
+    assert_eq!(stats.locked_count, 0);
+    assert_eq!(stats.approved_count, 1);
+    
+    let stats = client.get_employer_stats(&employer, &token_id);
+    assert_eq!(stats.locked, 0);
+    assert_eq!(stats.released, 300_0000000);
+    assert_eq!(stats.refunded, 50_0000000);
+    assert_eq!(stats.approved_count, 2);
+}
+
+#[test]
+fn test_escrows_by_status() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (usdc, _, usdc_admin) = create_token_contract(&env, &admin);
+    let (xlm, _, xlm_admin) = create_token_contract(&env, &admin);
+    usdc_admin.mint(&employer, &1000_0000000);
+    xlm_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let jobs = ["job-1", "job-2", "job-3", "job-4"];
+    let mut ids = std::vec::Vec::new();
+    for (i, job) in jobs.iter().enumerate() {
+        let token = if i == 3 { &xlm } else { &usdc };
+        let escrow = client.create_escrow_auto(
+            &String::from_str(&env, job),
+            &employer,
+            &Some(employee.clone()),
+            token,
+            &100_0000000,
+            &None
+        );
+        ids.push(escrow.id);
+    }
+    client.approve_escrow(&ids[1], &employer, &None);
+    
+    // Locked escrows page in creation order
+    let page = client.get_employer_escrows_by_status(&employer, &EscrowStatus::Locked, &None, &0, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get(0).unwrap().id, ids[0]);
+    assert_eq!(page.get(1).unwrap().id, ids[2]);
+    let page = client.get_employer_escrows_by_status(&employer, &EscrowStatus::Locked, &None, &2, &2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().id, ids[3]);
+    
+    // Token filter
+    let page = client.get_employer_escrows_by_status(&employer, &EscrowStatus::Locked, &Some(xlm), &0, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().id, ids[3]);
+    
+    // The approved escrow moved indexes for both parties
+    let page = client.get_employee_escrows_by_status(&employee, &EscrowStatus::Approved, &None, &0, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get(0).unwrap().status, EscrowStatus::Approved);
+    let page = client.get_employee_escrows_by_status(&employee, &EscrowStatus::Locked, &Some(usdc), &0, &10);
+    assert_eq!(page.len(), 2);
+}
+
+#[test]
+fn test_batch_create_and_approve() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee1 = Address::generate(&env);
+    let employee2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let requests = vec![
+        &env,
+        EscrowRequest {
+            escrow_id: String::from_str(&env, "escrow-a"),
+            job_id: String::from_str(&env, "job-1"),
+            employee: Some(employee1.clone()),
+            token: token_id.clone(),
+            amount: 100_0000000,
+            deadline: None,
+            claimable: false,
+        },
+        EscrowRequest {
+            escrow_id: String::from_str(&env, "escrow-b"),
+            job_id: String::from_str(&env, "job-2"),
+            employee: Some(employee2.clone()),
+            token: token_id.clone(),
+            amount: 200_0000000,
+            deadline: None,
+            claimable: false,
+        },
+    ];
+    let escrows = client.create_escrows(&employer, &requests);
+    assert_eq!(escrows.len(), 2);
+    assert_eq!(token_client.balance(&contract_id), 300_0000000);
+    
+    let ids = vec![&env, escrows.get(0).unwrap().id, escrows.get(1).unwrap().id];
+    let results = client.approve_escrows(&employer, &ids);
+    assert!(results.iter().all(|e| e.status == EscrowStatus::Approved));
+    assert_eq!(token_client.balance(&employee1), 100_0000000);
+    assert_eq!(token_client.balance(&employee2), 200_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Amount must be positive")]
+fn test_batch_create_atomic() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let requests = vec![
+        &env,
+        EscrowRequest {
+            escrow_id: String::from_str(&env, "escrow-a"),
+            job_id: String::from_str(&env, "job-1"),
+            employee: Some(Address::generate(&env)),
+            token: token_id.clone(),
+            amount: 100_0000000,
+            deadline: None,
+            claimable: false,
+        },
+        EscrowRequest {
+            escrow_id: String::from_str(&env, "escrow-b"),
+            job_id: String::from_str(&env, "job-2"),
+            employee: Some(Address::generate(&env)),
+            token: token_id,
+            amount: 0,
+            deadline: None,
+            claimable: false,
+        },
+    ];
+    client.create_escrows(&employer, &requests);
+}
+
+// Stand-in for a SEP-40 price oracle quoting in USD with 7 decimals
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_price(env: Env, asset: Asset, price: i128) {
+        let data = PriceData { price, timestamp: env.ledger().timestamp() };
+        env.storage().instance().set(&asset, &data);
+    }
+    
+    pub fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&asset)
+    }
+}
+
+#[test]
+fn test_pegged_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let oracle_id = env.register(MockOracle, ());
+    let oracle = MockOracleClient::new(&env, &oracle_id);
+    
+    // $0.10 per token; $50 of work backed by 800 tokens
+    let asset = Asset::Stellar(token_id.clone());
+    oracle.set_price(&asset, &1_000_000);
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_pegged_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &800_0000000,
+        &oracle_id,
+        &50_0000000,
+        &None
+    );
+    assert_eq!(client.get_pegged_payout(&escrow_id), 500_0000000);
+    
+    // Token rises to $0.125, so fewer tokens cover the same value
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    oracle.set_price(&asset, &1_250_000);
+    client.approve_escrow(&escrow_id, &employer, &None);
+    
+    assert_eq!(token_client.balance(&employee), 400_0000000);
+    assert_eq!(token_client.balance(&employer), 600_0000000);
+    assert_eq!(client.get_escrow(&escrow_id).released, 400_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Insufficient collateral")]
+fn test_pegged_escrow_undercollateralized() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let oracle_id = env.register(MockOracle, ());
+    let oracle = MockOracleClient::new(&env, &oracle_id);
+    
+    let asset = Asset::Stellar(token_id.clone());
+    oracle.set_price(&asset, &1_000_000);
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_pegged_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Address::generate(&env),
+        &token_id,
+        &800_0000000,
+        &oracle_id,
+        &50_0000000,
+        &None
+    );
+    
+    // Token halves to $0.05: 1000 tokens are now owed
+    oracle.set_price(&asset, &500_000);
+    client.approve_escrow(&escrow_id, &employer, &None);
+}
+
+#[test]
+#[should_panic(expected = "Stale price")]
+fn test_pegged_escrow_stale_price() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    let oracle_id = env.register(MockOracle, ());
+    let oracle = MockOracleClient::new(&env, &oracle_id);
+    
+    oracle.set_price(&Asset::Stellar(token_id.clone()), &1_000_000);
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_pegged_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Address::generate(&env),
+        &token_id,
+        &800_0000000,
+        &oracle_id,
+        &50_0000000,
+        &None
+    );
+    
+    env.ledger().with_mut(|li| li.timestamp = 24 * 60 * 60);
+    client.approve_escrow(&escrow_id, &employer, &None);
+}
+
+#[test]
+fn test_reassign_employee_with_consent() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let replacement = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    client.reassign_employee(&escrow_id, &employer, &replacement);
+    
+    // The current employee had to sign off
+    assert!(env.auths().iter().any(|(address, _)| *address == employee));
+    
+    let reassignments = client.get_reassignments(&escrow_id);
+    assert_eq!(reassignments.len(), 1);
+    assert_eq!(reassignments.get(0).unwrap().from, employee);
+    assert!(!reassignments.get(0).unwrap().abandoned);
+    assert_eq!(client.get_employee_escrows(&employee).len(), 0);
+    assert_eq!(client.get_employee_escrows(&replacement), vec![&env, escrow_id.clone()]);
+    assert_eq!(client.get_employee_stats(&employee, &token_id).locked, 0);
+    
+    client.approve_escrow(&escrow_id, &employer, &None);
+    assert_eq!(token_client.balance(&replacement), 100_0000000);
+    assert_eq!(token_client.balance(&employee), 0);
+}
+
+#[test]
+fn test_reassign_abandoned_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let replacement = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    // Checking in pushes the timeout back
+    env.ledger().with_mut(|li| li.timestamp = 10 * 24 * 60 * 60);
+    client.check_in(&escrow_id, &employee);
+    assert_eq!(client.get_last_activity(&escrow_id), 10 * 24 * 60 * 60);
+    
+    env.ledger().with_mut(|li| li.timestamp = 41 * 24 * 60 * 60);
+    client.reassign_employee(&escrow_id, &employer, &replacement);
+    
+    // Only the employer signed
+    assert!(env.auths().iter().all(|(address, _)| *address == employer));
+    assert!(client.get_reassignments(&escrow_id).get(0).unwrap().abandoned);
+    assert_eq!(client.get_escrow(&escrow_id).employee, Some(replacement));
+}
+
+#[test]
+fn test_reassign_after_bond_forfeited() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let replacement = Address::generate(&env);
+    let arbiter = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    token_admin.mint(&employee, &100_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.require_bond(&escrow_id, &employer, &token_id, &50_0000000, &arbiter);
+    client.post_bond(&escrow_id, &employee);
+    client.propose_settlement(&escrow_id, &employee, &40_0000000);
+    
+    // Nothing is left to return once the whole bond is forfeited
+    client.forfeit_bond(&escrow_id, &arbiter, &50_0000000);
+    client.reassign_employee(&escrow_id, &employer, &replacement);
+    
+    assert_eq!(client.get_escrow(&escrow_id).employee, Some(replacement.clone()));
+    assert_eq!(client.get_settlement(&escrow_id), None);
+    
+    // The replacement has to post the bond again
+    let bond = client.get_bond(&escrow_id).unwrap();
+    assert!(!bond.posted);
+    assert_eq!(bond.forfeited, 0);
+    token_admin.mint(&replacement, &50_0000000);
+    client.post_bond(&escrow_id, &replacement);
+    assert_eq!(token_client.balance(&replacement), 0);
+}
+
+#[test]
+fn test_reassign_resets_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let replacement = Address::generate(&env);
+    let manager1 = Address::generate(&env);
+    let manager2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.set_approvers(&escrow_id, &employer, &vec![&env, manager1.clone(), manager2.clone()], &2);
+    client.approve_escrow(&escrow_id, &manager1, &None);
+    
+    client.reassign_employee(&escrow_id, &employer, &replacement);
+    assert!(client.get_escrow(&escrow_id).approvals.is_empty());
+    
+    // manager1's sign-off on the old employee's work no longer counts
+    client.approve_escrow(&escrow_id, &manager2, &None);
+    assert_eq!(client.get_escrow(&escrow_id).status, EscrowStatus::Locked);
+    assert_eq!(token_client.balance(&replacement), 0);
+    
+    client.approve_escrow(&escrow_id, &manager1, &None);
+    assert_eq!(token_client.balance(&replacement), 100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Only standard and pegged escrows can be reassigned")]
+fn test_reassign_hourly_escrow() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let replacement = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_hourly_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &employee,
+        &token_id,
+        &25_0000000,
+        &500_0000000,
+        &None
+    );
+    client.reassign_employee(&escrow_id, &employer, &replacement);
+}
+
+#[test]
+fn test_negotiated_settlement() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    // Employee asks for 40 of the 100 for the work done so far
+    let settlement = client.propose_settlement(&escrow_id, &employee, &40_0000000);
+    assert_eq!(settlement.refund, 60_0000000);
+    
+    let escrow = client.accept_settlement(&escrow_id, &employer);
+    assert_eq!(escrow.status, EscrowStatus::Settled);
+    assert_eq!(escrow.released, 40_0000000);
+    assert_eq!(token_client.balance(&employee), 40_0000000);
+    assert_eq!(token_client.balance(&employer), 960_0000000);
+    assert_eq!(client.get_settlement(&escrow_id), None);
+    assert_eq!(client.get_employer_stats(&employer, &token_id).settled_count, 1);
+}
+
+#[test]
+fn test_settlement_dropped_when_escrow_closes() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(employee.clone()),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.propose_settlement(&escrow_id, &employee, &40_0000000);
+    
+    // Cancelling outright leaves nothing to settle
+    client.cancel_escrow(&escrow_id, &employer);
+    assert_eq!(client.get_settlement(&escrow_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Cannot accept your own proposal")]
+fn test_settlement_needs_other_party() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow_id = String::from_str(&env, "escrow-1");
+    client.create_escrow(
+        &escrow_id,
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(Address::generate(&env)),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    client.propose_settlement(&escrow_id, &employer, &10_0000000);
+    client.accept_settlement(&escrow_id, &employer);
+}
+
+#[test]
+fn test_escrow_history() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let employee = Address::generate(&env);
+    let manager1 = Address::generate(&env);
+    let manager2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    let escrow = client.create_claimable_escrow(
+        &String::from_str(&env, "escrow-1"),
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    client.claim_escrow(&escrow.id, &employee);
+    client.top_up_escrow(&escrow.id, &employer, &20_0000000);
+    client.set_approvers(&escrow.id, &employer, &vec![&env, manager1.clone(), manager2.clone()], &2);
+    
+    env.ledger().with_mut(|li| li.timestamp = 500);
+    client.approve_escrow(&escrow.id, &manager1, &None);
+    client.approve_escrow(&escrow.id, &manager2, &None);
+    
+    let record = client.get_escrow(&escrow.id);
+    assert_eq!(record.approved_at, Some(500));
+    assert_eq!(record.closed_at, Some(500));
+    
+    let history = client.get_escrow_history(&escrow.id);
+    let actions: std::vec::Vec<(EscrowAction, Address, i128)> =
+        history.iter().map(|e| (e.action, e.actor, e.amount)).collect();
+    assert_eq!(
+        actions,
+        [
+            (EscrowAction::Created, employer.clone(), 100_0000000),
+            (EscrowAction::Assigned, employee, 0),
+            (EscrowAction::Amended, employer.clone(), 120_0000000),
+            (EscrowAction::ApproversSet, employer, 2),
+            (EscrowAction::Approval, manager1, 0),
+            (EscrowAction::Approved, manager2, 120_0000000),
+        ]
+    );
+    assert_eq!(history.get(0).unwrap().timestamp, 100);
+}
+
+#[test]
+fn test_cancelled_escrow_closed_at() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let employer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&employer, &1000_0000000);
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(EscrowContract, (&owner,));
+    let client = EscrowContractClient::new(&env, &contract_id);
+    
+    let escrow = client.create_escrow_auto(
+        &String::from_str(&env, "job-1"),
+        &employer,
+        &Some(Address::generate(&env)),
+        &token_id,
+        &100_0000000,
+        &None
+    );
+    
+    env.ledger().with_mut(|li| li.timestamp = 700);
+    client.cancel_escrow(&escrow.id, &employer);
+    
+    let record = client.get_escrow(&escrow.id);
+    assert_eq!(record.approved_at, None);
+    assert_eq!(record.closed_at, Some(700));
+    let last = client.get_escrow_history(&escrow.id).last().unwrap();
+    assert_eq!(last.action, EscrowAction::Cancelled);
+    assert_eq!(last.actor, employer);
+}
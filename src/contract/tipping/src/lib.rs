@@ -7,6 +7,9 @@ mod test;
 #[contract]
 pub struct TippingContract;
 
+// Default cap on tip message length (in bytes)
+const DEFAULT_MAX_MESSAGE_LEN: u32 = 280;
+
 // Single tip record
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -16,8 +19,59 @@ pub struct Tip {
     pub from: Address,           // Tipper (employer)
     pub to: Address,             // Recipient (employee)
     pub amount: i128,            // Amount in stroops
+    pub token: Address,          // Token the tip was paid in
     pub message: String,         // Optional tip message
     pub timestamp: u64,
+    pub campaign_id: Option<u64>, // Campaign this tip contributed to, if any
+    pub matched_tip_id: Option<u64>, // Tip this one matches, if paid from a sponsor pool
+    pub status: TipStatus,       // Settlement state
+    pub settles_at: Option<u64>, // End of the grace period for delayed tips
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum TipStatus {
+    Settled,     // Funds delivered to the recipient
+    Pending,     // Held by the contract during the grace period
+    Revoked,     // Sender revoked during the grace period, funds returned
+    Refunded,    // Campaign missed its goal, contribution returned
+}
+
+// Crowdfunding campaign run by a creator
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Campaign {
+    pub id: u64,
+    pub creator: Address,        // Who receives the funds if the goal is met
+    pub token: Address,          // Token contributions must be made in
+    pub goal: i128,              // Target amount in stroops
+    pub deadline: u64,           // Contributions close at this timestamp
+    pub raised: i128,            // Total contributed so far
+    pub withdrawn: bool,         // Creator already withdrew the funds
+    pub created_at: u64,
+}
+
+// Sponsor-funded pool that matches tips in a token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MatchingPool {
+    pub id: u64,
+    pub sponsor: Address,        // Who funded the pool
+    pub token: Address,          // Only tips in this token are matched
+    pub ratio_bps: u32,          // Match ratio in basis points (10000 = 1:1)
+    pub recipient_cap: i128,     // Max matched amount per recipient
+    pub global_cap: i128,        // Max matched amount across all recipients
+    pub balance: i128,           // Unspent funds held by the contract
+    pub matched: i128,           // Total matched so far
+    pub active: bool,            // False once the sponsor closes the pool
+}
+
+// Admin-configured limits for an allowed token
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenConfig {
+    pub min_amount: i128,        // Smallest tip allowed in stroops
+    pub max_amount: i128,        // Largest tip allowed in stroops
 }
 
 // Storage keys
@@ -27,6 +81,20 @@ pub enum DataKey {
     Tip(u64),                   // Individual tip by ID
     UserTipsReceived(Address),  // List of tip IDs received by user
     UserTipsSent(Address),      // List of tip IDs sent by user
+    CampaignCounter,            // Global campaign counter
+    Campaign(u64),              // Individual campaign by ID
+    CampaignTips(u64),          // List of tip IDs contributed to a campaign
+    Contribution(u64, Address), // Refundable amount per (campaign, contributor)
+    UserCampaigns(Address),     // List of campaign IDs created by user
+    PoolCounter,                // Global matching pool counter
+    MatchingPool(u64),          // Individual matching pool by ID
+    TokenPools(Address),        // List of matching pool IDs by token
+    PoolMatched(u64, Address),  // Amount matched per (pool, recipient)
+    Admin,                      // Manages the token allowlist
+    TokenConfig(Address),       // Limits for an allowed token
+    AllowedTokens,              // List of allowed token addresses
+    MaxMessageLength,           // Max tip message length in bytes
+    JobTips(String),            // List of tip IDs for a job
 }
 
 #[contractimpl]
@@ -52,15 +120,11 @@ impl TippingContract {
         // Prevent tipping yourself
         assert!(from != to, "Cannot tip yourself");
         
+        // Enforce token allowlist and message limits
+        check_tip(&env, &token, amount, &message);
+        
         // Generate unique tip ID
-        let tip_id: u64 = env
-            .storage()
-            .instance()
-            .get(&DataKey::TipCounter)
-            .unwrap_or(0);
-        env.storage()
-            .instance()
-            .set(&DataKey::TipCounter, &(tip_id + 1));
+        let tip_id = next_tip_id(&env);
         
         // Transfer tokens from sender to recipient
         let token_client = token::Client::new(&env, &token);
@@ -73,34 +137,131 @@ impl TippingContract {
             from: from.clone(),
             to: to.clone(),
             amount,
+            token: token.clone(),
             message,
             timestamp: env.ledger().timestamp(),
+            campaign_id: None,
+            matched_tip_id: None,
+            status: TipStatus::Settled,
+            settles_at: None,
         };
         
         // Store tip
         env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
         
         // Add to recipient's received tips list
-        let mut received_tips: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserTipsReceived(to.clone()))
-            .unwrap_or(Vec::new(&env));
-        received_tips.push_back(tip_id);
-        env.storage().instance().set(&DataKey::UserTipsReceived(to), &received_tips);
+        append_id(&env, DataKey::UserTipsReceived(to.clone()), tip_id);
         
         // Add to sender's sent tips list
-        let mut sent_tips: Vec<u64> = env
-            .storage()
-            .instance()
-            .get(&DataKey::UserTipsSent(from.clone()))
-            .unwrap_or(Vec::new(&env));
-        sent_tips.push_back(tip_id);
-        env.storage().instance().set(&DataKey::UserTipsSent(from), &sent_tips);
+        append_id(&env, DataKey::UserTipsSent(from.clone()), tip_id);
+        
+        // Add to job's tip list
+        index_job_tip(&env, &tip.job_id, tip_id);
+        
+        // Top up the tip from any sponsor pools running for this token
+        apply_matches(&env, &tip);
         
         tip_id
     }
     
+    /// Send a tip that settles after a grace period
+    /// Funds are held by the contract; the sender can revoke until `grace_period` seconds pass
+    #[allow(clippy::too_many_arguments)]
+    pub fn send_delayed_tip(
+        env: Env,
+        job_id: String,
+        from: Address,
+        to: Address,
+        token: Address,
+        amount: i128,
+        message: String,
+        grace_period: u64,
+    ) -> u64 {
+        // Verify sender signed transaction
+        from.require_auth();
+        
+        assert!(amount > 0, "Tip amount must be positive");
+        assert!(from != to, "Cannot tip yourself");
+        assert!(grace_period > 0, "Grace period must be positive");
+        
+        // Enforce token allowlist and message limits
+        check_tip(&env, &token, amount, &message);
+        
+        // Generate unique tip ID
+        let tip_id = next_tip_id(&env);
+        
+        // Hold tokens in the contract until the tip is finalized or revoked
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&from, &env.current_contract_address(), &amount);
+        
+        let tip = Tip {
+            id: tip_id,
+            job_id,
+            from: from.clone(),
+            to: to.clone(),
+            amount,
+            token,
+            message,
+            timestamp: env.ledger().timestamp(),
+            campaign_id: None,
+            matched_tip_id: None,
+            status: TipStatus::Pending,
+            settles_at: Some(env.ledger().timestamp() + grace_period),
+        };
+        env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
+        
+        // Index right away so both sides can see the pending tip
+        append_id(&env, DataKey::UserTipsReceived(to), tip_id);
+        append_id(&env, DataKey::UserTipsSent(from), tip_id);
+        index_job_tip(&env, &tip.job_id, tip_id);
+        
+        tip_id
+    }
+    
+    /// Revoke a pending tip during its grace period and return the funds
+    pub fn revoke_tip(env: Env, tip_id: u64, from: Address) {
+        // Verify sender signed transaction
+        from.require_auth();
+        
+        let mut tip = Self::get_tip(env.clone(), tip_id);
+        
+        assert!(tip.from == from, "Unauthorized");
+        assert!(tip.status == TipStatus::Pending, "Tip is not pending");
+        assert!(tip.campaign_id.is_none(), "Campaign tips settle with the campaign");
+        assert!(
+            env.ledger().timestamp() < tip.settles_at.unwrap_or(0),
+            "Grace period has ended"
+        );
+        
+        let token_client = token::Client::new(&env, &tip.token);
+        token_client.transfer(&env.current_contract_address(), &from, &tip.amount);
+        
+        tip.status = TipStatus::Revoked;
+        env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
+    }
+    
+    /// Deliver a pending tip once its grace period has passed
+    /// Anyone can call this (usually the recipient)
+    pub fn finalize_tip(env: Env, tip_id: u64) {
+        let mut tip = Self::get_tip(env.clone(), tip_id);
+        
+        assert!(tip.status == TipStatus::Pending, "Tip is not pending");
+        assert!(tip.campaign_id.is_none(), "Campaign tips settle with the campaign");
+        assert!(
+            env.ledger().timestamp() >= tip.settles_at.unwrap_or(0),
+            "Grace period not over"
+        );
+        
+        let token_client = token::Client::new(&env, &tip.token);
+        token_client.transfer(&env.current_contract_address(), &tip.to, &tip.amount);
+        
+        tip.status = TipStatus::Settled;
+        env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
+        
+        // Sponsor pools match the tip once it actually settles
+        apply_matches(&env, &tip);
+    }
+    
     /// Get a single tip by ID
     pub fn get_tip(env: Env, tip_id: u64) -> Tip {
         env.storage()
@@ -143,13 +304,46 @@ impl TippingContract {
         tips
     }
     
+    /// Get all tips for a job
+    pub fn get_job_tips(env: Env, job_id: String) -> Vec<Tip> {
+        let tip_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::JobTips(job_id))
+            .unwrap_or(Vec::new(&env));
+        
+        let mut tips = Vec::new(&env);
+        for id in tip_ids.iter() {
+            if let Some(tip) = env.storage().instance().get::<_, Tip>(&DataKey::Tip(id)) {
+                tips.push_back(tip);
+            }
+        }
+        tips
+    }
+    
+    /// Get total amount tipped on a job in a given token
+    pub fn get_job_tip_total(env: Env, job_id: String, token: Address) -> i128 {
+        let tips = Self::get_job_tips(env, job_id);
+        let mut total: i128 = 0;
+        
+        for tip in tips.iter() {
+            if tip.token == token && tip.status == TipStatus::Settled {
+                total += tip.amount;
+            }
+        }
+        total
+    }
+    
     /// Get total amount of tips received by a user
     pub fn get_total_tips_received(env: Env, user: Address) -> i128 {
         let tips = Self::get_tips_received(env, user);
         let mut total: i128 = 0;
         
+        // Pending and revoked tips haven't moved funds to the recipient
         for tip in tips.iter() {
-            total += tip.amount;
+            if tip.status == TipStatus::Settled {
+                total += tip.amount;
+            }
         }
         total
     }
@@ -159,8 +353,11 @@ impl TippingContract {
         let tips = Self::get_tips_sent(env, user);
         let mut total: i128 = 0;
         
+        // Pending and revoked tips haven't moved funds to the recipient
         for tip in tips.iter() {
-            total += tip.amount;
+            if tip.status == TipStatus::Settled {
+                total += tip.amount;
+            }
         }
         total
     }
@@ -176,4 +373,613 @@ impl TippingContract {
         let tips = Self::get_tips_sent(env, user);
         tips.len()
     }
-}
\ No newline at end of file
+    
+    /// Start a crowdfunding campaign
+    /// Contributions are held by the contract until the goal is met or the deadline passes
+    pub fn create_campaign(
+        env: Env,
+        creator: Address,
+        token: Address,
+        goal: i128,
+        deadline: u64,
+    ) -> u64 {
+        // Verify creator signed transaction
+        creator.require_auth();
+        
+        assert!(goal > 0, "Goal must be positive");
+        assert!(deadline > env.ledger().timestamp(), "Deadline must be in the future");
+        check_token_allowed(&env, &token);
+        
+        // Generate unique campaign ID
+        let campaign_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignCounter)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::CampaignCounter, &(campaign_id + 1));
+        
+        let campaign = Campaign {
+            id: campaign_id,
+            creator: creator.clone(),
+            token,
+            goal,
+            deadline,
+            raised: 0,
+            withdrawn: false,
+            created_at: env.ledger().timestamp(),
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::Campaign(campaign_id), &campaign);
+        
+        // Add to creator's campaign list
+        append_id(&env, DataKey::UserCampaigns(creator), campaign_id);
+        
+        campaign_id
+    }
+    
+    /// Contribute to a campaign
+    /// Funds are held by the contract and recorded as a tip linked to the campaign
+    pub fn contribute(
+        env: Env,
+        campaign_id: u64,
+        from: Address,
+        amount: i128,
+        message: String,
+    ) -> u64 {
+        // Verify contributor signed transaction
+        from.require_auth();
+        
+        assert!(amount > 0, "Tip amount must be positive");
+        
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        
+        assert!(from != campaign.creator, "Cannot tip yourself");
+        assert!(!campaign.withdrawn, "Campaign already withdrawn");
+        assert!(env.ledger().timestamp() < campaign.deadline, "Campaign has ended");
+        check_tip(&env, &campaign.token, amount, &message);
+        
+        // Hold tokens in the contract until the campaign settles
+        let token_client = token::Client::new(&env, &campaign.token);
+        token_client.transfer(&from, &env.current_contract_address(), &amount);
+        
+        campaign.raised += amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::Campaign(campaign_id), &campaign);
+        
+        // Track refundable amount for this contributor
+        let contribution_key = DataKey::Contribution(campaign_id, from.clone());
+        let contributed: i128 = env
+            .storage()
+            .instance()
+            .get(&contribution_key)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&contribution_key, &(contributed + amount));
+        
+        // Generate unique tip ID
+        let tip_id = next_tip_id(&env);
+        
+        let tip = Tip {
+            id: tip_id,
+            job_id: String::from_str(&env, ""),
+            from: from.clone(),
+            to: campaign.creator,
+            amount,
+            token: campaign.token,
+            message,
+            timestamp: env.ledger().timestamp(),
+            campaign_id: Some(campaign_id),
+            matched_tip_id: None,
+            status: TipStatus::Pending,   // Held until the campaign settles
+            settles_at: None,
+        };
+        env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
+        
+        // Add to campaign's tip list
+        append_id(&env, DataKey::CampaignTips(campaign_id), tip_id);
+        
+        // Add to sender's sent tips list
+        // (the creator's received list is only credited once funds are withdrawn)
+        append_id(&env, DataKey::UserTipsSent(from), tip_id);
+        
+        tip_id
+    }
+    
+    /// Withdraw campaign funds to the creator once the goal is reached
+    pub fn withdraw_campaign(env: Env, campaign_id: u64, creator: Address) -> i128 {
+        // Verify creator signed transaction
+        creator.require_auth();
+        
+        let mut campaign = Self::get_campaign(env.clone(), campaign_id);
+        
+        assert!(campaign.creator == creator, "Unauthorized");
+        assert!(!campaign.withdrawn, "Campaign already withdrawn");
+        assert!(campaign.raised >= campaign.goal, "Campaign goal not reached");
+        
+        // Release held tokens to the creator
+        let token_client = token::Client::new(&env, &campaign.token);
+        token_client.transfer(&env.current_contract_address(), &creator, &campaign.raised);
+        
+        campaign.withdrawn = true;
+        env.storage()
+            .instance()
+            .set(&DataKey::Campaign(campaign_id), &campaign);
+        
+        // Contributions now count as tips received by the creator
+        let campaign_tips: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignTips(campaign_id))
+            .unwrap_or(Vec::new(&env));
+        let mut received_tips: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::UserTipsReceived(creator.clone()))
+            .unwrap_or(Vec::new(&env));
+        for tip_id in campaign_tips.iter() {
+            received_tips.push_back(tip_id);
+            
+            let mut tip: Tip = env.storage().instance().get(&DataKey::Tip(tip_id)).unwrap();
+            tip.status = TipStatus::Settled;
+            env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
+        }
+        env.storage().instance().set(&DataKey::UserTipsReceived(creator), &received_tips);
+        
+        campaign.raised
+    }
+    
+    /// Claim back a contribution after a campaign missed its goal
+    /// Each contributor pulls their own refund
+    pub fn claim_refund(env: Env, campaign_id: u64, contributor: Address) -> i128 {
+        // Verify contributor signed transaction
+        contributor.require_auth();
+        
+        let campaign = Self::get_campaign(env.clone(), campaign_id);
+        
+        assert!(env.ledger().timestamp() >= campaign.deadline, "Campaign still active");
+        assert!(campaign.raised < campaign.goal, "Campaign goal reached");
+        
+        let contribution_key = DataKey::Contribution(campaign_id, contributor.clone());
+        let amount: i128 = env
+            .storage()
+            .instance()
+            .get(&contribution_key)
+            .unwrap_or(0);
+        assert!(amount > 0, "Nothing to refund");
+        
+        // Clear before transferring so a refund can only be claimed once
+        env.storage().instance().set(&contribution_key, &0i128);
+        
+        let token_client = token::Client::new(&env, &campaign.token);
+        token_client.transfer(&env.current_contract_address(), &contributor, &amount);
+        
+        // The contributor's tips to this campaign never reached the creator
+        let campaign_tips: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignTips(campaign_id))
+            .unwrap_or(Vec::new(&env));
+        for tip_id in campaign_tips.iter() {
+            let mut tip: Tip = env.storage().instance().get(&DataKey::Tip(tip_id)).unwrap();
+            if tip.from == contributor && tip.status == TipStatus::Pending {
+                tip.status = TipStatus::Refunded;
+                env.storage().instance().set(&DataKey::Tip(tip_id), &tip);
+            }
+        }
+        
+        amount
+    }
+    
+    /// Get a single campaign by ID
+    pub fn get_campaign(env: Env, campaign_id: u64) -> Campaign {
+        env.storage()
+            .instance()
+            .get(&DataKey::Campaign(campaign_id))
+            .expect("Campaign not found")
+    }
+    
+    /// Get all contributions made to a campaign
+    pub fn get_campaign_tips(env: Env, campaign_id: u64) -> Vec<Tip> {
+        let tip_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::CampaignTips(campaign_id))
+            .unwrap_or(Vec::new(&env));
+        
+        let mut tips = Vec::new(&env);
+        for id in tip_ids.iter() {
+            if let Some(tip) = env.storage().instance().get::<_, Tip>(&DataKey::Tip(id)) {
+                tips.push_back(tip);
+            }
+        }
+        tips
+    }
+    
+    /// Get all campaigns created by a user
+    pub fn get_user_campaigns(env: Env, user: Address) -> Vec<Campaign> {
+        let campaign_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::UserCampaigns(user))
+            .unwrap_or(Vec::new(&env));
+        
+        let mut campaigns = Vec::new(&env);
+        for id in campaign_ids.iter() {
+            if let Some(campaign) = env.storage().instance().get::<_, Campaign>(&DataKey::Campaign(id)) {
+                campaigns.push_back(campaign);
+            }
+        }
+        campaigns
+    }
+    
+    /// Get the refundable amount a contributor has in a campaign
+    pub fn get_contribution(env: Env, campaign_id: u64, contributor: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::Contribution(campaign_id, contributor))
+            .unwrap_or(0)
+    }
+    
+    /// Create a sponsor-funded matching pool
+    /// Every qualifying tip in `token` is matched at `ratio_bps` until a cap or the balance runs out
+    pub fn create_matching_pool(
+        env: Env,
+        sponsor: Address,
+        token: Address,
+        amount: i128,
+        ratio_bps: u32,
+        recipient_cap: i128,
+        global_cap: i128,
+    ) -> u64 {
+        // Verify sponsor signed transaction
+        sponsor.require_auth();
+        
+        assert!(amount > 0, "Pool amount must be positive");
+        assert!(ratio_bps > 0, "Match ratio must be positive");
+        assert!(recipient_cap > 0 && global_cap > 0, "Caps must be positive");
+        check_token_allowed(&env, &token);
+        
+        // Lock sponsor funds in the contract
+        let token_client = token::Client::new(&env, &token);
+        token_client.transfer(&sponsor, &env.current_contract_address(), &amount);
+        
+        // Generate unique pool ID
+        let pool_id: u64 = env
+            .storage()
+            .instance()
+            .get(&DataKey::PoolCounter)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::PoolCounter, &(pool_id + 1));
+        
+        let pool = MatchingPool {
+            id: pool_id,
+            sponsor,
+            token: token.clone(),
+            ratio_bps,
+            recipient_cap,
+            global_cap,
+            balance: amount,
+            matched: 0,
+            active: true,
+        };
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchingPool(pool_id), &pool);
+        
+        // Register pool for its token so send_tip can find it
+        append_id(&env, DataKey::TokenPools(token), pool_id);
+        
+        pool_id
+    }
+    
+    /// Add more funds to an active matching pool
+    pub fn fund_matching_pool(env: Env, pool_id: u64, sponsor: Address, amount: i128) {
+        // Verify sponsor signed transaction
+        sponsor.require_auth();
+        
+        assert!(amount > 0, "Pool amount must be positive");
+        
+        let mut pool = Self::get_matching_pool(env.clone(), pool_id);
+        assert!(pool.sponsor == sponsor, "Unauthorized");
+        assert!(pool.active, "Pool is closed");
+        
+        let token_client = token::Client::new(&env, &pool.token);
+        token_client.transfer(&sponsor, &env.current_contract_address(), &amount);
+        
+        pool.balance += amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchingPool(pool_id), &pool);
+    }
+    
+    /// Close a matching pool and return the unspent balance to the sponsor
+    pub fn close_matching_pool(env: Env, pool_id: u64, sponsor: Address) -> i128 {
+        // Verify sponsor signed transaction
+        sponsor.require_auth();
+        
+        let mut pool = Self::get_matching_pool(env.clone(), pool_id);
+        assert!(pool.sponsor == sponsor, "Unauthorized");
+        assert!(pool.active, "Pool is closed");
+        
+        let remaining = pool.balance;
+        if remaining > 0 {
+            let token_client = token::Client::new(&env, &pool.token);
+            token_client.transfer(&env.current_contract_address(), &sponsor, &remaining);
+        }
+        
+        pool.balance = 0;
+        pool.active = false;
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchingPool(pool_id), &pool);
+        
+        // Later tips only need to look at open pools
+        let pools_key = DataKey::TokenPools(pool.token);
+        let mut pool_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&pools_key)
+            .unwrap_or(Vec::new(&env));
+        if let Some(i) = pool_ids.first_index_of(pool_id) {
+            pool_ids.remove(i);
+        }
+        env.storage().instance().set(&pools_key, &pool_ids);
+        
+        remaining
+    }
+    
+    /// Get a single matching pool by ID
+    pub fn get_matching_pool(env: Env, pool_id: u64) -> MatchingPool {
+        env.storage()
+            .instance()
+            .get(&DataKey::MatchingPool(pool_id))
+            .expect("Pool not found")
+    }
+    
+    /// Get the open matching pools for a token
+    pub fn get_token_pools(env: Env, token: Address) -> Vec<MatchingPool> {
+        let pool_ids: Vec<u64> = env
+            .storage()
+            .instance()
+            .get(&DataKey::TokenPools(token))
+            .unwrap_or(Vec::new(&env));
+        
+        let mut pools = Vec::new(&env);
+        for id in pool_ids.iter() {
+            if let Some(pool) = env.storage().instance().get::<_, MatchingPool>(&DataKey::MatchingPool(id)) {
+                pools.push_back(pool);
+            }
+        }
+        pools
+    }
+    
+    /// Get how much a pool has matched for a recipient
+    pub fn get_pool_matched(env: Env, pool_id: u64, recipient: Address) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::PoolMatched(pool_id, recipient))
+            .unwrap_or(0)
+    }
+    
+    /// Set the admin that manages the token allowlist at deployment
+    /// The allowlist starts empty: the admin must `allow_token` before any tips go through
+    pub fn __constructor(env: Env, admin: Address) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+    }
+    
+    /// Allow a token for tipping, or update its limits
+    pub fn allow_token(env: Env, admin: Address, token: Address, min_amount: i128, max_amount: i128) {
+        require_admin(&env, &admin);
+        
+        assert!(min_amount > 0, "Minimum must be positive");
+        assert!(max_amount >= min_amount, "Maximum must be at least minimum");
+        
+        let config = TokenConfig { min_amount, max_amount };
+        let key = DataKey::TokenConfig(token.clone());
+        if !env.storage().instance().has(&key) {
+            let mut tokens: Vec<Address> = env
+                .storage()
+                .instance()
+                .get(&DataKey::AllowedTokens)
+                .unwrap_or(Vec::new(&env));
+            tokens.push_back(token);
+            env.storage().instance().set(&DataKey::AllowedTokens, &tokens);
+        }
+        env.storage().instance().set(&key, &config);
+    }
+    
+    /// Remove a token from the allowlist
+    pub fn remove_token(env: Env, admin: Address, token: Address) {
+        require_admin(&env, &admin);
+        
+        env.storage()
+            .instance()
+            .remove(&DataKey::TokenConfig(token.clone()));
+        
+        let mut tokens: Vec<Address> = env
+            .storage()
+            .instance()
+            .get(&DataKey::AllowedTokens)
+            .unwrap_or(Vec::new(&env));
+        if let Some(index) = tokens.first_index_of(&token) {
+            tokens.remove(index);
+            env.storage().instance().set(&DataKey::AllowedTokens, &tokens);
+        }
+    }
+    
+    /// Set the maximum tip message length in bytes
+    pub fn set_max_message_length(env: Env, admin: Address, max_length: u32) {
+        require_admin(&env, &admin);
+        
+        env.storage()
+            .instance()
+            .set(&DataKey::MaxMessageLength, &max_length);
+    }
+    
+    /// Get the admin address
+    pub fn get_admin(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Admin).unwrap()
+    }
+    
+    /// Get limits for a token (None if not allowed)
+    pub fn get_token_config(env: Env, token: Address) -> Option<TokenConfig> {
+        env.storage().instance().get(&DataKey::TokenConfig(token))
+    }
+    
+    /// Get all allowed tokens
+    pub fn get_allowed_tokens(env: Env) -> Vec<Address> {
+        env.storage()
+            .instance()
+            .get(&DataKey::AllowedTokens)
+            .unwrap_or(Vec::new(&env))
+    }
+    
+    /// Get the maximum tip message length in bytes
+    pub fn get_max_message_length(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::MaxMessageLength)
+            .unwrap_or(DEFAULT_MAX_MESSAGE_LEN)
+    }
+}
+
+// Reserve the next tip ID
+fn next_tip_id(env: &Env) -> u64 {
+    let tip_id: u64 = env
+        .storage()
+        .instance()
+        .get(&DataKey::TipCounter)
+        .unwrap_or(0);
+    env.storage()
+        .instance()
+        .set(&DataKey::TipCounter, &(tip_id + 1));
+    tip_id
+}
+
+// Append an ID to the list stored under `key`
+fn append_id(env: &Env, key: DataKey, id: u64) {
+    let mut ids: Vec<u64> = env
+        .storage()
+        .instance()
+        .get(&key)
+        .unwrap_or(Vec::new(env));
+    ids.push_back(id);
+    env.storage().instance().set(&key, &ids);
+}
+
+// Add a tip to its job's list (standalone tips have no job)
+fn index_job_tip(env: &Env, job_id: &String, tip_id: u64) {
+    if job_id.is_empty() {
+        return;
+    }
+    append_id(env, DataKey::JobTips(job_id.clone()), tip_id);
+}
+
+// Pay matched amounts from every active pool for the tip's token
+// Each match is recorded as its own tip from the sponsor, linked to the original
+fn apply_matches(env: &Env, tip: &Tip) {
+    let token = &tip.token;
+    
+    let pool_ids: Vec<u64> = env
+        .storage()
+        .instance()
+        .get(&DataKey::TokenPools(token.clone()))
+        .unwrap_or(Vec::new(env));
+    
+    for pool_id in pool_ids.iter() {
+        let mut pool: MatchingPool = match env.storage().instance().get(&DataKey::MatchingPool(pool_id)) {
+            Some(pool) => pool,
+            None => continue,
+        };
+        
+        // Sponsors can't match tips they send or receive
+        if !pool.active || pool.balance <= 0 || pool.sponsor == tip.from || pool.sponsor == tip.to {
+            continue;
+        }
+        
+        let matched_key = DataKey::PoolMatched(pool_id, tip.to.clone());
+        let recipient_matched: i128 = env.storage().instance().get(&matched_key).unwrap_or(0);
+        
+        // Limit by ratio, both caps and remaining balance
+        let mut match_amount = tip.amount * pool.ratio_bps as i128 / 10_000;
+        match_amount = match_amount.min(pool.recipient_cap - recipient_matched);
+        match_amount = match_amount.min(pool.global_cap - pool.matched);
+        match_amount = match_amount.min(pool.balance);
+        if match_amount <= 0 {
+            continue;
+        }
+        
+        let token_client = token::Client::new(env, token);
+        token_client.transfer(&env.current_contract_address(), &tip.to, &match_amount);
+        
+        pool.balance -= match_amount;
+        pool.matched += match_amount;
+        env.storage()
+            .instance()
+            .set(&DataKey::MatchingPool(pool_id), &pool);
+        env.storage()
+            .instance()
+            .set(&matched_key, &(recipient_matched + match_amount));
+        
+        let match_id = next_tip_id(env);
+        let match_tip = Tip {
+            id: match_id,
+            job_id: tip.job_id.clone(),
+            from: pool.sponsor.clone(),
+            to: tip.to.clone(),
+            amount: match_amount,
+            token: token.clone(),
+            message: String::from_str(env, ""),
+            timestamp: env.ledger().timestamp(),
+            campaign_id: None,
+            matched_tip_id: Some(tip.id),
+            status: TipStatus::Settled,
+            settles_at: None,
+        };
+        env.storage().instance().set(&DataKey::Tip(match_id), &match_tip);
+        append_id(env, DataKey::UserTipsReceived(tip.to.clone()), match_id);
+        append_id(env, DataKey::UserTipsSent(pool.sponsor), match_id);
+        index_job_tip(env, &match_tip.job_id, match_id);
+    }
+}
+
+// Verify `admin` is the configured admin and signed the transaction
+fn require_admin(env: &Env, admin: &Address) {
+    admin.require_auth();
+    
+    let stored: Address = env
+        .storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .unwrap();
+    assert!(stored == *admin, "Unauthorized");
+}
+
+// Reject tokens missing from the allowlist
+fn check_token_allowed(env: &Env, token: &Address) -> TokenConfig {
+    env.storage()
+        .instance()
+        .get(&DataKey::TokenConfig(token.clone()))
+        .expect("Token not allowed")
+}
+
+// Validate a tip against the token limits and message length
+fn check_tip(env: &Env, token: &Address, amount: i128, message: &String) {
+    let config = check_token_allowed(env, token);
+    assert!(amount >= config.min_amount, "Tip amount below minimum");
+    assert!(amount <= config.max_amount, "Tip amount above maximum");
+    
+    let max_length: u32 = env
+        .storage()
+        .instance()
+        .get(&DataKey::MaxMessageLength)
+        .unwrap_or(DEFAULT_MAX_MESSAGE_LEN);
+    assert!(message.len() <= max_length, "Message too long");
+}
@@ -2,7 +2,7 @@
 
 use super::*;
 use soroban_sdk::{
-    testutils::Address as _,
+    testutils::{Address as _, Ledger},
     token, Address, Env, String,
 };
 
@@ -23,7 +23,8 @@ fn test_send_tip() {
     env.mock_all_auths();
     
     // Register tipping contract
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     // Create test addresses
@@ -33,6 +34,7 @@ fn test_send_tip() {
     
     // Create mock token
     let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
     
     // Mint tokens to tipper
     token_admin.mint(&tipper, &1000_0000000); // 1000 USDC
@@ -63,7 +65,8 @@ fn test_get_tip() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper = Address::generate(&env);
@@ -71,6 +74,7 @@ fn test_get_tip() {
     let admin = Address::generate(&env);
     
     let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
     token_admin.mint(&tipper, &1000_0000000);
     
     // Send tip
@@ -99,7 +103,8 @@ fn test_get_tips_received() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper1 = Address::generate(&env);
@@ -108,6 +113,7 @@ fn test_get_tips_received() {
     let admin = Address::generate(&env);
     
     let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
     token_admin.mint(&tipper1, &1000_0000000);
     token_admin.mint(&tipper2, &1000_0000000);
     
@@ -143,7 +149,8 @@ fn test_get_total_tips_received() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper1 = Address::generate(&env);
@@ -152,6 +159,7 @@ fn test_get_total_tips_received() {
     let admin = Address::generate(&env);
     
     let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
     token_admin.mint(&tipper1, &1000_0000000);
     token_admin.mint(&tipper2, &1000_0000000);
     
@@ -186,7 +194,8 @@ fn test_zero_amount() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper = Address::generate(&env);
@@ -210,7 +219,8 @@ fn test_tip_yourself() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper = Address::generate(&env);
@@ -232,7 +242,8 @@ fn test_tip_with_empty_job_id() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper = Address::generate(&env);
@@ -240,6 +251,7 @@ fn test_tip_with_empty_job_id() {
     let admin = Address::generate(&env);
     
     let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
     token_admin.mint(&tipper, &1000_0000000);
     
     // Tip with empty job_id (standalone tip)
@@ -261,7 +273,8 @@ fn test_multiple_tips_increment_ids() {
     let env = Env::default();
     env.mock_all_auths();
     
-    let contract_id = env.register(TippingContract, ());
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
     let client = TippingContractClient::new(&env, &contract_id);
     
     let tipper = Address::generate(&env);
@@ -269,6 +282,7 @@ fn test_multiple_tips_increment_ids() {
     let admin = Address::generate(&env);
     
     let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
     token_admin.mint(&tipper, &1000_0000000);
     
     // Send 3 tips and verify IDs increment
@@ -302,4 +316,728 @@ fn test_multiple_tips_increment_ids() {
     assert_eq!(id1, 0);
     assert_eq!(id2, 1);
     assert_eq!(id3, 2);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_campaign_goal_reached_withdraw() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let creator = Address::generate(&env);
+    let backer1 = Address::generate(&env);
+    let backer2 = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&backer1, &1000_0000000);
+    token_admin.mint(&backer2, &1000_0000000);
+    
+    let campaign_id = client.create_campaign(&creator, &token_id, &300_0000000, &1000);
+    
+    let tip_id = client.contribute(
+        &campaign_id,
+        &backer1,
+        &100_0000000,
+        &String::from_str(&env, "Go for it")
+    );
+    client.contribute(
+        &campaign_id,
+        &backer2,
+        &200_0000000,
+        &String::from_str(&env, "Ship it")
+    );
+    
+    // Funds are held by the contract, not the creator
+    assert_eq!(token_client.balance(&contract_id), 300_0000000);
+    assert_eq!(token_client.balance(&creator), 0);
+    
+    // Contributions are recorded as tips linked to the campaign
+    let tip = client.get_tip(&tip_id);
+    assert_eq!(tip.to, creator);
+    assert_eq!(tip.campaign_id, Some(campaign_id));
+    assert_eq!(tip.status, TipStatus::Pending);
+    assert_eq!(client.get_campaign_tips(&campaign_id).len(), 2);
+    assert_eq!(client.get_campaign(&campaign_id).raised, 300_0000000);
+    
+    let withdrawn = client.withdraw_campaign(&campaign_id, &creator);
+    assert_eq!(withdrawn, 300_0000000);
+    assert_eq!(token_client.balance(&creator), 300_0000000);
+    assert!(client.get_campaign(&campaign_id).withdrawn);
+    assert_eq!(client.get_total_tips_received(&creator), 300_0000000);
+    assert_eq!(client.get_tip(&tip_id).status, TipStatus::Settled);
+    assert_eq!(client.get_total_tips_sent(&backer1), 100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Campaign goal not reached")]
+fn test_campaign_withdraw_before_goal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let creator = Address::generate(&env);
+    let backer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&backer, &1000_0000000);
+    
+    let campaign_id = client.create_campaign(&creator, &token_id, &300_0000000, &1000);
+    client.contribute(
+        &campaign_id,
+        &backer,
+        &100_0000000,
+        &String::from_str(&env, "Partial")
+    );
+    
+    client.withdraw_campaign(&campaign_id, &creator);
+}
+
+#[test]
+fn test_campaign_refund_after_missed_goal() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let creator = Address::generate(&env);
+    let backer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&backer, &1000_0000000);
+    
+    let campaign_id = client.create_campaign(&creator, &token_id, &300_0000000, &1000);
+    let tip_id = client.contribute(
+        &campaign_id,
+        &backer,
+        &100_0000000,
+        &String::from_str(&env, "Partial")
+    );
+    assert_eq!(token_client.balance(&backer), 900_0000000);
+    
+    // Move past the deadline
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    
+    let refunded = client.claim_refund(&campaign_id, &backer);
+    assert_eq!(refunded, 100_0000000);
+    assert_eq!(token_client.balance(&backer), 1000_0000000);
+    assert_eq!(client.get_contribution(&campaign_id, &backer), 0);
+    
+    // Refunded contributions don't count as tips sent
+    assert_eq!(client.get_tip(&tip_id).status, TipStatus::Refunded);
+    assert_eq!(client.get_total_tips_sent(&backer), 0);
+}
+
+#[test]
+#[should_panic(expected = "Campaign still active")]
+fn test_campaign_refund_before_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let creator = Address::generate(&env);
+    let backer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&backer, &1000_0000000);
+    
+    let campaign_id = client.create_campaign(&creator, &token_id, &300_0000000, &1000);
+    client.contribute(
+        &campaign_id,
+        &backer,
+        &100_0000000,
+        &String::from_str(&env, "Partial")
+    );
+    
+    client.claim_refund(&campaign_id, &backer);
+}
+
+#[test]
+#[should_panic(expected = "Campaign has ended")]
+fn test_campaign_contribute_after_deadline() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let creator = Address::generate(&env);
+    let backer = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&backer, &1000_0000000);
+    
+    let campaign_id = client.create_campaign(&creator, &token_id, &300_0000000, &1000);
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    
+    client.contribute(
+        &campaign_id,
+        &backer,
+        &100_0000000,
+        &String::from_str(&env, "Too late")
+    );
+}
+
+#[test]
+fn test_matching_pool_matches_tip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let sponsor = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&sponsor, &1000_0000000);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    // 1:1 match, up to 150 per recipient and 500 overall
+    let pool_id = client.create_matching_pool(
+        &sponsor,
+        &token_id,
+        &500_0000000,
+        &10_000,
+        &150_0000000,
+        &500_0000000
+    );
+    
+    let tip_id = client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks")
+    );
+    
+    // Recipient gets the tip plus the match
+    assert_eq!(token_client.balance(&recipient), 200_0000000);
+    
+    let tips = client.get_tips_received(&recipient);
+    assert_eq!(tips.len(), 2);
+    let matched = tips.get(1).unwrap();
+    assert_eq!(matched.from, sponsor);
+    assert_eq!(matched.amount, 100_0000000);
+    assert_eq!(matched.matched_tip_id, Some(tip_id));
+    
+    // Second tip only gets matched up to the per-recipient cap
+    client.send_tip(
+        &String::from_str(&env, "job-2"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "More thanks")
+    );
+    assert_eq!(token_client.balance(&recipient), 350_0000000);
+    assert_eq!(client.get_pool_matched(&pool_id, &recipient), 150_0000000);
+    
+    let pool = client.get_matching_pool(&pool_id);
+    assert_eq!(pool.balance, 350_0000000);
+    assert_eq!(pool.matched, 150_0000000);
+}
+
+#[test]
+fn test_matching_pool_ignores_other_tokens() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let sponsor = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (pool_token, _, pool_token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &pool_token, &1, &i128::MAX);
+    let (tip_token, tip_token_client, tip_token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &tip_token, &1, &i128::MAX);
+    pool_token_admin.mint(&sponsor, &1000_0000000);
+    tip_token_admin.mint(&tipper, &1000_0000000);
+    
+    client.create_matching_pool(
+        &sponsor,
+        &pool_token,
+        &500_0000000,
+        &10_000,
+        &150_0000000,
+        &500_0000000
+    );
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &tip_token,
+        &100_0000000,
+        &String::from_str(&env, "Thanks")
+    );
+    
+    assert_eq!(tip_token_client.balance(&recipient), 100_0000000);
+    assert_eq!(client.get_tips_received(&recipient).len(), 1);
+}
+
+#[test]
+fn test_close_matching_pool_returns_balance() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let sponsor = Address::generate(&env);
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&sponsor, &1000_0000000);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    // Half match with a small global cap
+    let pool_id = client.create_matching_pool(
+        &sponsor,
+        &token_id,
+        &500_0000000,
+        &5_000,
+        &500_0000000,
+        &20_0000000
+    );
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks")
+    );
+    assert_eq!(token_client.balance(&recipient), 120_0000000);
+    
+    let remaining = client.close_matching_pool(&pool_id, &sponsor);
+    assert_eq!(remaining, 480_0000000);
+    assert_eq!(token_client.balance(&sponsor), 980_0000000);
+    assert!(!client.get_matching_pool(&pool_id).active);
+    assert_eq!(client.get_token_pools(&token_id).len(), 0);
+    
+    // Closed pools stop matching
+    client.send_tip(
+        &String::from_str(&env, "job-2"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks again")
+    );
+    assert_eq!(token_client.balance(&recipient), 220_0000000);
+}
+
+#[test]
+fn test_allowlisted_token_tip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    client.allow_token(&owner, &token_id, &1_0000000, &500_0000000);
+    
+    assert_eq!(client.get_allowed_tokens().len(), 1);
+    assert_eq!(
+        client.get_token_config(&token_id),
+        Some(TokenConfig { min_amount: 1_0000000, max_amount: 500_0000000 })
+    );
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks")
+    );
+    assert_eq!(token_client.balance(&recipient), 100_0000000);
+}
+
+#[test]
+#[should_panic(expected = "Token not allowed")]
+fn test_tip_unlisted_token() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Tip amount below minimum")]
+fn test_tip_below_minimum() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    client.allow_token(&owner, &token_id, &1_0000000, &500_0000000);
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &1000,
+        &String::from_str(&env, "Dust")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Message too long")]
+fn test_tip_message_too_long() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    client.allow_token(&owner, &token_id, &1_0000000, &500_0000000);
+    client.set_max_message_length(&owner, &5);
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Way too long")
+    );
+}
+
+#[test]
+#[should_panic(expected = "Token not allowed")]
+fn test_removed_token_rejected() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    client.allow_token(&owner, &token_id, &1_0000000, &500_0000000);
+    client.remove_token(&owner, &token_id);
+    assert_eq!(client.get_allowed_tokens().len(), 0);
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks")
+    );
+}
+
+#[test]
+fn test_get_job_tips() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper1 = Address::generate(&env);
+    let tipper2 = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (usdc, _, usdc_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &usdc, &1, &i128::MAX);
+    let (xlm, _, xlm_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &xlm, &1, &i128::MAX);
+    usdc_admin.mint(&tipper1, &1000_0000000);
+    xlm_admin.mint(&tipper2, &1000_0000000);
+    
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper1,
+        &recipient,
+        &usdc,
+        &100_0000000,
+        &String::from_str(&env, "Tip 1")
+    );
+    client.send_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper2,
+        &recipient,
+        &xlm,
+        &40_0000000,
+        &String::from_str(&env, "Tip 2")
+    );
+    client.send_tip(
+        &String::from_str(&env, "job-2"),
+        &tipper1,
+        &recipient,
+        &usdc,
+        &25_0000000,
+        &String::from_str(&env, "Tip 3")
+    );
+    
+    let job_tips = client.get_job_tips(&String::from_str(&env, "job-1"));
+    assert_eq!(job_tips.len(), 2);
+    assert_eq!(job_tips.get(0).unwrap().token, usdc);
+    assert_eq!(job_tips.get(1).unwrap().token, xlm);
+    
+    assert_eq!(client.get_job_tip_total(&String::from_str(&env, "job-1"), &usdc), 100_0000000);
+    assert_eq!(client.get_job_tip_total(&String::from_str(&env, "job-1"), &xlm), 40_0000000);
+    assert_eq!(client.get_job_tip_total(&String::from_str(&env, "job-2"), &usdc), 25_0000000);
+}
+
+#[test]
+fn test_standalone_tips_not_indexed_by_job() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    client.send_tip(
+        &String::from_str(&env, ""),
+        &tipper,
+        &recipient,
+        &token_id,
+        &50_0000000,
+        &String::from_str(&env, "Random tip")
+    );
+    
+    assert_eq!(client.get_job_tips(&String::from_str(&env, "")).len(), 0);
+}
+
+#[test]
+fn test_delayed_tip_finalize() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    let tip_id = client.send_delayed_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks"),
+        &3600
+    );
+    
+    // Held by the contract and not yet counted
+    let tip = client.get_tip(&tip_id);
+    assert_eq!(tip.status, TipStatus::Pending);
+    assert_eq!(tip.settles_at, Some(3600));
+    assert_eq!(token_client.balance(&contract_id), 100_0000000);
+    assert_eq!(client.get_total_tips_received(&recipient), 0);
+    
+    env.ledger().with_mut(|li| li.timestamp = 3600);
+    client.finalize_tip(&tip_id);
+    
+    assert_eq!(client.get_tip(&tip_id).status, TipStatus::Settled);
+    assert_eq!(token_client.balance(&recipient), 100_0000000);
+    assert_eq!(client.get_total_tips_received(&recipient), 100_0000000);
+}
+
+#[test]
+fn test_revoke_delayed_tip() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, token_client, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    let tip_id = client.send_delayed_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Oops"),
+        &3600
+    );
+    
+    client.revoke_tip(&tip_id, &tipper);
+    
+    assert_eq!(client.get_tip(&tip_id).status, TipStatus::Revoked);
+    assert_eq!(token_client.balance(&tipper), 1000_0000000);
+    assert_eq!(token_client.balance(&recipient), 0);
+    assert_eq!(client.get_total_tips_sent(&tipper), 0);
+}
+
+#[test]
+#[should_panic(expected = "Grace period has ended")]
+fn test_revoke_after_grace_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    let tip_id = client.send_delayed_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks"),
+        &3600
+    );
+    
+    env.ledger().with_mut(|li| li.timestamp = 3600);
+    client.revoke_tip(&tip_id, &tipper);
+}
+
+#[test]
+#[should_panic(expected = "Grace period not over")]
+fn test_finalize_during_grace_period() {
+    let env = Env::default();
+    env.mock_all_auths();
+    
+    let owner = Address::generate(&env);
+    let contract_id = env.register(TippingContract, (&owner,));
+    let client = TippingContractClient::new(&env, &contract_id);
+    
+    let tipper = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let admin = Address::generate(&env);
+    
+    let (token_id, _, token_admin) = create_token_contract(&env, &admin);
+    client.allow_token(&owner, &token_id, &1, &i128::MAX);
+    token_admin.mint(&tipper, &1000_0000000);
+    
+    let tip_id = client.send_delayed_tip(
+        &String::from_str(&env, "job-1"),
+        &tipper,
+        &recipient,
+        &token_id,
+        &100_0000000,
+        &String::from_str(&env, "Thanks"),
+        &3600
+    );
+    
+    client.finalize_tip(&tip_id);
+}
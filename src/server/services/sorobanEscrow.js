@@ -117,7 +117,8 @@ export async function approveEscrow(escrowId, employerAddress) {
     
     const args = [
       xdr.ScVal.scvString(escrowId),
-      employerAddr
+      employerAddr,
+      xdr.ScVal.scvVoid()                   // feedback (none)
     ];
     
     return {